@@ -17,6 +17,7 @@ pub mod link;
 pub mod llemu;
 pub mod misc;
 pub mod motor;
+pub mod optical;
 pub mod rotation;
 pub mod rtos;
 pub mod vision;
@@ -32,6 +33,7 @@ pub use link::*;
 pub use llemu::*;
 pub use misc::*;
 pub use motor::*;
+pub use optical::*;
 pub use rotation::*;
 pub use rtos::*;
 pub use vision::*;
@@ -50,4 +52,21 @@ extern "C" {
     pub fn free(ptr: *mut core::ffi::c_void);
     pub fn __errno() -> *mut i32;
     pub fn clock() -> i32;
+
+    /// Opens a file. Files on the SD card are prefixed with `/usd/`.
+    pub fn fopen(filename: *const core::ffi::c_char, mode: *const core::ffi::c_char) -> *mut FILE;
+    pub fn fclose(stream: *mut FILE) -> core::ffi::c_int;
+    pub fn fread(
+        ptr: *mut core::ffi::c_void,
+        size: usize,
+        count: usize,
+        stream: *mut FILE,
+    ) -> usize;
+    pub fn ferror(stream: *mut FILE) -> core::ffi::c_int;
+}
+
+/// An open file handle from the C standard library.
+#[repr(C)]
+pub struct FILE {
+    _private: [u8; 0],
 }
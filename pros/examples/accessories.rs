@@ -4,8 +4,20 @@
 use core::time::Duration;
 use pros::{prelude::*, task::delay};
 
-#[derive(Debug, Default)]
-struct ExampleRobot;
+struct ExampleRobot {
+    motor: Motor,
+    vision: VisionSensor,
+}
+impl ExampleRobot {
+    pub fn new(peripherals: Peripherals) -> Self {
+        Self {
+            // Create a new motor plugged into port 2. The motor will brake when not moving.
+            motor: Motor::new(peripherals.port_2, BrakeMode::Brake).unwrap(),
+            vision: VisionSensor::new(peripherals.port_9, VisionZeroPoint::Center).unwrap(),
+        }
+    }
+}
+
 #[async_trait]
 impl AsyncRobot for ExampleRobot {
     async fn opcontrol(&mut self) -> pros::Result {
@@ -18,14 +30,12 @@ impl AsyncRobot for ExampleRobot {
 
         pros::async_runtime::block_on(handle);
 
-        // Create a new motor plugged into port 2. The motor will brake when not moving.
-        let motor = Motor::new(2, BrakeMode::Brake)?;
+        let motor = self.motor;
         motor.wait_until_stopped().await?;
         // Create a controller, specifically controller 1.
         let controller = Controller::Master;
 
-        let mut vision = VisionSensor::new(9, VisionZeroPoint::Center)?;
-        vision.set_led(LedMode::On(Rgb::new(0, 0, 255)));
+        self.vision.set_led(LedMode::On(Rgb::new(0, 0, 255)))?;
 
         pros::lcd::buttons::register(left_button_callback, Button::Left);
 
@@ -49,14 +59,20 @@ impl AsyncRobot for ExampleRobot {
             motor.set_output(controller.state().joysticks.right.y)?;
 
             // println!("pid out {}", pid.update(10.0, motor.position().into_degrees() as f32));
-            println!("Vision objs {}", vision.nth_largest_object(0)?.middle_x);
+            println!(
+                "Vision objs {}",
+                self.vision.nth_largest_object(0)?.middle_x
+            );
 
             // Once again, sleep.
             sleep(Duration::from_millis(20)).await;
         }
     }
 }
-async_robot!(ExampleRobot);
+async_robot!(
+    ExampleRobot,
+    ExampleRobot::new(Peripherals::take().unwrap())
+);
 
 fn left_button_callback() {
     println!("Left button pressed!");
@@ -4,17 +4,24 @@
 use core::time::Duration;
 use pros::prelude::*;
 
-#[derive(Default)]
-pub struct Robot;
+pub struct Robot {
+    imu: InertialSensor,
+}
+
+impl Robot {
+    pub fn new(peripherals: Peripherals) -> Self {
+        Self {
+            imu: InertialSensor::new(peripherals.port_1).unwrap(),
+        }
+    }
+}
 
 impl SyncRobot for Robot {
     fn opcontrol(&mut self) -> pros::Result {
-        let imu = InertialSensor::new(1)?;
-
-        imu.calibrate()?;
+        self.imu.calibrate_blocking()?;
 
         loop {
-            let euler = imu.euler()?;
+            let euler = self.imu.euler()?;
 
             println!(
                 "Pitch: {} Roll: {} Yaw: {}",
@@ -26,4 +33,4 @@ impl SyncRobot for Robot {
     }
 }
 
-sync_robot!(Robot);
+sync_robot!(Robot, Robot::new(Peripherals::take().unwrap()));
@@ -0,0 +1,116 @@
+//! Analog ADI inputs.
+//!
+//! Analog inputs read a voltage from 0 to 5 volts as a 12-bit value, and are used by sensors
+//! such as line trackers, light sensors and potentiometers.
+//!
+//! Sensors like line trackers read differently depending on the lighting, so they should be
+//! [calibrated](AdiAnalogIn::calibrate) while the robot is still at the start of a match.
+//! Calibrated readings are then relative to the value measured during calibration.
+//!
+//! Example of detecting a white line with a line tracker:
+//! ```rust
+//! let mut line_tracker = AdiAnalogIn::new(peripherals.adi_a)?;
+//! line_tracker.calibrate()?;
+//! loop {
+//!     // Line trackers read lower values over lighter surfaces.
+//!     if line_tracker.calibrated_value()? < -500 {
+//!         println!("Found the line!");
+//!     }
+//!     sleep(Duration::from_millis(10)).await;
+//! }
+//! ```
+
+use pros_sys::PROS_ERR;
+
+use super::{AdiError, AdiPort};
+use crate::error::bail_on;
+
+/// The largest value an analog input can read.
+pub const ADI_ANALOG_MAX_VALUE: u16 = 4095;
+/// The voltage an analog input reads at [`ADI_ANALOG_MAX_VALUE`].
+pub const ADI_ANALOG_MAX_VOLTAGE: f64 = 5.0;
+
+/// A generic analog input plugged into a three-wire port.
+#[derive(Debug)]
+pub struct AdiAnalogIn {
+    port: AdiPort,
+    calibrated: bool,
+}
+
+impl AdiAnalogIn {
+    /// Configures a three-wire port as an analog input.
+    pub fn new(port: AdiPort) -> Result<Self, AdiError> {
+        port.configure(pros_sys::E_ADI_ANALOG_IN)?;
+        Ok(Self {
+            port,
+            calibrated: false,
+        })
+    }
+
+    /// Returns the raw 12-bit value of the input, from 0 to [`ADI_ANALOG_MAX_VALUE`].
+    pub fn value(&self) -> Result<u16, AdiError> {
+        let value = unsafe { bail_on!(PROS_ERR, pros_sys::adi_analog_read(self.port.index())) };
+        Ok(value as u16)
+    }
+
+    /// Returns the voltage of the input, from 0 to 5 volts.
+    pub fn voltage(&self) -> Result<f64, AdiError> {
+        Ok(self.normalized()? * ADI_ANALOG_MAX_VOLTAGE)
+    }
+
+    /// Returns the value of the input scaled to be between 0 and 1.
+    pub fn normalized(&self) -> Result<f64, AdiError> {
+        Ok(self.value()? as f64 / ADI_ANALOG_MAX_VALUE as f64)
+    }
+
+    /// Calibrates the sensor by averaging its value over half a second, and returns the average.
+    ///
+    /// This blocks the current task while calibrating, and the sensor's reading should not be
+    /// changing while it runs.
+    pub fn calibrate(&mut self) -> Result<u16, AdiError> {
+        let value =
+            unsafe { bail_on!(PROS_ERR, pros_sys::adi_analog_calibrate(self.port.index())) };
+        self.calibrated = true;
+        Ok(value as u16)
+    }
+
+    /// Returns whether the sensor has been calibrated.
+    pub fn is_calibrated(&self) -> bool {
+        self.calibrated
+    }
+
+    /// Returns the difference between the value of the input and the value measured during calibration,
+    /// from -4095 to 4095.
+    ///
+    /// This is rounded to whole values, so [`calibrated_value_high_res`](Self::calibrated_value_high_res)
+    /// should be used for readings that will be integrated over time.
+    pub fn calibrated_value(&self) -> Result<i16, AdiError> {
+        if !self.calibrated {
+            return Err(AdiError::NotCalibrated);
+        }
+        let value = unsafe {
+            bail_on!(
+                PROS_ERR,
+                pros_sys::adi_analog_read_calibrated(self.port.index())
+            )
+        };
+        Ok(value as i16)
+    }
+
+    /// Returns the difference between the value of the input and the value measured during calibration
+    /// with 4 extra bits of precision.
+    ///
+    /// This is the calibrated value multiplied by 16, which avoids round-off error building up
+    /// when the value is integrated (such as for gyros and accelerometers).
+    pub fn calibrated_value_high_res(&self) -> Result<i32, AdiError> {
+        if !self.calibrated {
+            return Err(AdiError::NotCalibrated);
+        }
+        Ok(unsafe {
+            bail_on!(
+                PROS_ERR,
+                pros_sys::adi_analog_read_calibrated_HR(self.port.index())
+            )
+        })
+    }
+}
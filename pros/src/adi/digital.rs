@@ -0,0 +1,111 @@
+//! Digital ADI inputs and outputs.
+//!
+//! Digital inputs read whether a three-wire device (such as a limit switch or bumper) is pressed,
+//! and digital outputs switch a three-wire device (such as a pneumatic solenoid) on and off.
+//!
+//! Example of firing a piston when a limit switch is pressed:
+//! ```rust
+//! let mut switch = AdiDigitalIn::new(peripherals.adi_a)?;
+//! let mut solenoid = AdiDigitalOut::new(peripherals.adi_b)?;
+//! loop {
+//!     if switch.new_press()? {
+//!         solenoid.toggle()?;
+//!     }
+//!     sleep(Duration::from_millis(10)).await;
+//! }
+//! ```
+
+use pros_sys::PROS_ERR;
+
+use super::{AdiError, AdiPort};
+use crate::error::bail_on;
+
+/// A digital input, such as a limit switch or bumper, plugged into a three-wire port.
+#[derive(Debug)]
+pub struct AdiDigitalIn {
+    port: AdiPort,
+}
+
+impl AdiDigitalIn {
+    /// Configures a three-wire port as a digital input.
+    pub fn new(port: AdiPort) -> Result<Self, AdiError> {
+        port.configure(pros_sys::E_ADI_DIGITAL_IN)?;
+        Ok(Self { port })
+    }
+
+    /// Returns whether the input is high.
+    ///
+    /// Switches and bumpers are high while they are pressed.
+    pub fn is_high(&self) -> Result<bool, AdiError> {
+        let value = unsafe { bail_on!(PROS_ERR, pros_sys::adi_digital_read(self.port.index())) };
+        Ok(value != 0)
+    }
+
+    /// Returns whether the input is low.
+    pub fn is_low(&self) -> Result<bool, AdiError> {
+        Ok(!self.is_high()?)
+    }
+
+    /// Returns whether the input has become high since the last time this was called.
+    ///
+    /// This is useful for doing something once each time a button is pressed rather than
+    /// for as long as it is held.
+    pub fn new_press(&mut self) -> Result<bool, AdiError> {
+        let value = unsafe {
+            bail_on!(
+                PROS_ERR,
+                pros_sys::adi_digital_get_new_press(self.port.index())
+            )
+        };
+        Ok(value != 0)
+    }
+}
+
+/// A digital output, such as a pneumatic solenoid, plugged into a three-wire port.
+#[derive(Debug)]
+pub struct AdiDigitalOut {
+    port: AdiPort,
+    high: bool,
+}
+
+impl AdiDigitalOut {
+    /// Configures a three-wire port as a digital output that starts low.
+    pub fn new(port: AdiPort) -> Result<Self, AdiError> {
+        port.configure(pros_sys::E_ADI_DIGITAL_OUT)?;
+        let mut output = Self { port, high: false };
+        output.set_value(false)?;
+        Ok(output)
+    }
+
+    /// Sets the output high (`true`) or low (`false`).
+    pub fn set_value(&mut self, high: bool) -> Result<(), AdiError> {
+        unsafe {
+            bail_on!(
+                PROS_ERR,
+                pros_sys::adi_digital_write(self.port.index(), high)
+            );
+        }
+        self.high = high;
+        Ok(())
+    }
+
+    /// Sets the output high.
+    pub fn set_high(&mut self) -> Result<(), AdiError> {
+        self.set_value(true)
+    }
+
+    /// Sets the output low.
+    pub fn set_low(&mut self) -> Result<(), AdiError> {
+        self.set_value(false)
+    }
+
+    /// Switches the output between high and low.
+    pub fn toggle(&mut self) -> Result<(), AdiError> {
+        self.set_value(!self.high)
+    }
+
+    /// Returns whether the output was last set high.
+    pub fn is_high(&self) -> bool {
+        self.high
+    }
+}
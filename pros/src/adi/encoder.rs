@@ -0,0 +1,101 @@
+//! ADI quadrature encoders.
+//!
+//! The legacy optical shaft encoder uses two three-wire ports to count how far a shaft has turned,
+//! with 360 ticks per revolution. Its two wires must be plugged into a pair of neighboring ports:
+//! A and B, C and D, E and F, or G and H.
+//!
+//! Example of driving until a tracking wheel has turned five times:
+//! ```rust
+//! let mut encoder = AdiEncoder::new(peripherals.adi_a, peripherals.adi_b, false)?;
+//! encoder.zero()?;
+//! motor.set_output(0.5)?;
+//! while encoder.position()?.into_rotations() < 5.0 {
+//!     sleep(Duration::from_millis(10)).await;
+//! }
+//! motor.brake()?;
+//! ```
+
+use pros_sys::{adi_encoder_t, PROS_ERR};
+
+use super::{AdiError, AdiPort};
+use crate::{error::bail_on, position::Position, sensors::Encoder};
+
+/// An optical shaft encoder plugged into two three-wire ports.
+///
+/// The encoder stops counting when it is dropped.
+#[derive(Debug)]
+pub struct AdiEncoder {
+    raw: adi_encoder_t,
+    top: AdiPort,
+    bottom: AdiPort,
+}
+
+impl AdiEncoder {
+    /// Configures two three-wire ports as an encoder.
+    ///
+    /// `top` and `bottom` are the wires from the top and bottom of the encoder with the removable cover facing up.
+    /// If `reversed` is true, the encoder counts in the opposite direction.
+    pub fn new(top: AdiPort, bottom: AdiPort, reversed: bool) -> Result<Self, AdiError> {
+        validate_port_pair(&top, &bottom)?;
+        let raw = unsafe {
+            bail_on!(
+                PROS_ERR,
+                pros_sys::adi_encoder_init(top.index(), bottom.index(), reversed)
+            )
+        };
+        Ok(Self { raw, top, bottom })
+    }
+
+    /// Returns the ports the top and bottom wires of the encoder are plugged into.
+    pub fn ports(&self) -> (&AdiPort, &AdiPort) {
+        (&self.top, &self.bottom)
+    }
+
+    /// Returns the amount the encoder has turned since it was created or last zeroed.
+    pub fn position(&self) -> Result<Position, AdiError> {
+        let ticks = unsafe { bail_on!(PROS_ERR, pros_sys::adi_encoder_get(self.raw)) };
+        // There are 360 ticks in a revolution, so every tick is one degree.
+        Ok(Position::from_degrees(ticks as f64))
+    }
+
+    /// Sets the current position of the encoder to zero.
+    pub fn zero(&mut self) -> Result<(), AdiError> {
+        unsafe {
+            bail_on!(PROS_ERR, pros_sys::adi_encoder_reset(self.raw));
+        }
+        Ok(())
+    }
+}
+
+impl Encoder for AdiEncoder {
+    type Error = AdiError;
+
+    fn position(&self) -> Result<Position, AdiError> {
+        AdiEncoder::position(self)
+    }
+
+    fn zero(&mut self) -> Result<(), AdiError> {
+        AdiEncoder::zero(self)
+    }
+}
+
+impl Drop for AdiEncoder {
+    fn drop(&mut self) {
+        unsafe {
+            pros_sys::adi_encoder_shutdown(self.raw);
+        }
+    }
+}
+
+/// Checks that two ports are one of the neighboring pairs that two-wire devices can use.
+fn validate_port_pair(first: &AdiPort, second: &AdiPort) -> Result<(), AdiError> {
+    let lower = first.index().min(second.index());
+    let upper = first.index().max(second.index());
+    if upper != lower + 1 || lower % 2 == 0 {
+        return Err(AdiError::InvalidPortPair {
+            first: first.index(),
+            second: second.index(),
+        });
+    }
+    Ok(())
+}
@@ -0,0 +1,101 @@
+//! Legacy ADI yaw-rate gyroscopes.
+//!
+//! The legacy gyro measures how fast it is turning and adds up its readings into an angle,
+//! so it drifts over time and should be reset before each use.
+//! It calibrates for 1.3 seconds when it is first created, and must not be moved while calibrating.
+//!
+//! Gyros that read slightly too high or low can be corrected with a multiplier,
+//! found by turning the robot exactly 10 times and dividing 3600 by the rotation the gyro read.
+//!
+//! Example of printing the heading of the robot:
+//! ```rust
+//! let gyro = AdiGyro::new(peripherals.adi_b, 1.0)?;
+//! loop {
+//!     println!("Heading: {}", gyro.heading()?.into_degrees());
+//!     sleep(Duration::from_millis(100)).await;
+//! }
+//! ```
+
+use pros_sys::{adi_gyro_t, PROS_ERR, PROS_ERR_F};
+
+use super::{AdiError, AdiPort};
+use crate::{error::bail_on, position::Position, sensors::Gyro};
+
+/// A legacy gyroscope plugged into a three-wire port.
+///
+/// The gyro is disabled when it is dropped.
+#[derive(Debug)]
+pub struct AdiGyro {
+    raw: adi_gyro_t,
+    port: AdiPort,
+    multiplier: f64,
+}
+
+impl AdiGyro {
+    /// Configures a three-wire port as a gyro, and calibrates it if the port was not already a gyro.
+    ///
+    /// Every reading is scaled by `multiplier`, so `1.0` uses the gyro's readings as they are
+    /// and `-1.0` reverses the gyro's direction.
+    pub fn new(port: AdiPort, multiplier: f64) -> Result<Self, AdiError> {
+        let raw = unsafe { bail_on!(PROS_ERR, pros_sys::adi_gyro_init(port.index(), multiplier)) };
+        Ok(Self {
+            raw,
+            port,
+            multiplier,
+        })
+    }
+
+    /// Returns the port the gyro is plugged into.
+    pub fn port(&self) -> &AdiPort {
+        &self.port
+    }
+
+    /// Returns the multiplier applied to the gyro's readings.
+    pub fn multiplier(&self) -> f64 {
+        self.multiplier
+    }
+
+    /// Returns the total amount the gyro has turned since it was created or last reset, without wrapping around.
+    pub fn rotation(&self) -> Result<Position, AdiError> {
+        let tenths = unsafe { bail_on!(PROS_ERR_F, pros_sys::adi_gyro_get(self.raw)) };
+        Ok(Position::from_degrees(tenths / 10.0))
+    }
+
+    /// Returns the angle of the gyro, from 0 to 360 degrees.
+    pub fn heading(&self) -> Result<Position, AdiError> {
+        let heading = self.rotation()?.into_degrees() % 360.0;
+        Ok(Position::from_degrees(if heading < 0.0 {
+            heading + 360.0
+        } else {
+            heading
+        }))
+    }
+
+    /// Sets the current angle of the gyro to zero.
+    pub fn reset(&mut self) -> Result<(), AdiError> {
+        unsafe {
+            bail_on!(PROS_ERR, pros_sys::adi_gyro_reset(self.raw));
+        }
+        Ok(())
+    }
+}
+
+impl Gyro for AdiGyro {
+    type Error = AdiError;
+
+    fn heading(&self) -> Result<f64, AdiError> {
+        Ok(AdiGyro::heading(self)?.into_degrees())
+    }
+
+    fn rotation(&self) -> Result<f64, AdiError> {
+        Ok(AdiGyro::rotation(self)?.into_degrees())
+    }
+}
+
+impl Drop for AdiGyro {
+    fn drop(&mut self) {
+        unsafe {
+            pros_sys::adi_gyro_shutdown(self.raw);
+        }
+    }
+}
@@ -0,0 +1,150 @@
+//! ADI addressable LED strips.
+//!
+//! Addressable LED strips are plugged into a three-wire port and can have every pixel set to its own color.
+//! An [`AdiLed`] keeps a buffer of the color of every pixel, and sends the whole buffer to the strip whenever it changes.
+//!
+//! LED strips draw a lot of current, so it is recommended to keep every color channel at or below 128.
+//! To show a pattern that changes over time, see the [`animation`] module.
+//!
+//! Example of lighting up a strip of 30 LEDs:
+//! ```rust
+//! let mut led = AdiLed::new(peripherals.adi_h, 30)?;
+//! led.set_all(Rgb::new(0, 0, 128))?;
+//! led.set_pixel(0, Rgb::new(128, 0, 0))?;
+//! ```
+
+pub mod animation;
+
+use alloc::{vec, vec::Vec};
+use core::iter;
+
+use pros_sys::{adi_led_t, PROS_ERR};
+
+use super::{AdiError, AdiPort};
+use crate::{error::bail_on, sensors::vision::Rgb};
+
+/// The most pixels a single LED strip can have.
+pub const MAX_LED_LENGTH: usize = 64;
+
+/// An addressable LED strip plugged into a three-wire port.
+#[derive(Debug)]
+pub struct AdiLed {
+    raw: adi_led_t,
+    port: AdiPort,
+    buffer: Vec<u32>,
+}
+
+impl AdiLed {
+    /// Configures a three-wire port as an LED strip with `length` pixels.
+    ///
+    /// Every pixel starts off.
+    pub fn new(port: AdiPort, length: usize) -> Result<Self, AdiError> {
+        if length == 0 || length > MAX_LED_LENGTH {
+            return Err(AdiError::InvalidLedLength { length });
+        }
+        let raw = unsafe { bail_on!(PROS_ERR, pros_sys::adi_led_init(port.index())) };
+        Ok(Self {
+            raw,
+            port,
+            buffer: vec![0; length],
+        })
+    }
+
+    /// Returns the port the strip is plugged into.
+    pub fn port(&self) -> &AdiPort {
+        &self.port
+    }
+
+    /// Returns the number of pixels on the strip.
+    pub fn len(&self) -> usize {
+        self.buffer.len()
+    }
+
+    /// Returns whether the strip has no pixels.
+    ///
+    /// Strips always have at least one pixel, so this is always false.
+    pub fn is_empty(&self) -> bool {
+        self.buffer.is_empty()
+    }
+
+    /// Returns the color of a pixel, or `None` if the pixel is past the end of the strip.
+    pub fn pixel(&self, index: usize) -> Option<Rgb> {
+        self.buffer.get(index).map(|&color| color.into())
+    }
+
+    /// Returns the color of every pixel on the strip.
+    pub fn pixels(&self) -> impl Iterator<Item = Rgb> + '_ {
+        self.buffer.iter().map(|&color| color.into())
+    }
+
+    /// Sets the color of a single pixel.
+    pub fn set_pixel(&mut self, index: usize, color: Rgb) -> Result<(), AdiError> {
+        if index >= self.buffer.len() {
+            return Err(AdiError::PixelOutOfRange {
+                index,
+                length: self.buffer.len(),
+            });
+        }
+        unsafe {
+            bail_on!(
+                PROS_ERR,
+                pros_sys::adi_led_set_pixel(
+                    self.raw,
+                    self.buffer.as_mut_ptr(),
+                    self.buffer.len() as u32,
+                    color.into(),
+                    index as u32,
+                )
+            );
+        }
+        Ok(())
+    }
+
+    /// Sets every pixel on the strip to the same color.
+    pub fn set_all(&mut self, color: Rgb) -> Result<(), AdiError> {
+        unsafe {
+            bail_on!(
+                PROS_ERR,
+                pros_sys::adi_led_set_all(
+                    self.raw,
+                    self.buffer.as_mut_ptr(),
+                    self.buffer.len() as u32,
+                    color.into(),
+                )
+            );
+        }
+        Ok(())
+    }
+
+    /// Sets the color of every pixel at once, starting from the first pixel.
+    ///
+    /// Pixels past the end of `colors` are turned off, and extra colors are ignored.
+    pub fn set_buffer(&mut self, colors: &[Rgb]) -> Result<(), AdiError> {
+        let colors = colors.iter().copied().chain(iter::repeat(Rgb::BLACK));
+        for (pixel, color) in self.buffer.iter_mut().zip(colors) {
+            *pixel = color.into();
+        }
+        unsafe {
+            bail_on!(
+                PROS_ERR,
+                pros_sys::adi_led_set(self.raw, self.buffer.as_mut_ptr(), self.buffer.len() as u32)
+            );
+        }
+        Ok(())
+    }
+
+    /// Turns off every pixel on the strip.
+    pub fn clear(&mut self) -> Result<(), AdiError> {
+        unsafe {
+            bail_on!(
+                PROS_ERR,
+                pros_sys::adi_led_clear_all(
+                    self.raw,
+                    self.buffer.as_mut_ptr(),
+                    self.buffer.len() as u32,
+                )
+            );
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,238 @@
+//! Animated patterns for LED strips.
+//!
+//! A [`LedPattern`] describes what every pixel of a strip should look like at any point in time.
+//! [`LedAnimation::spawn`] takes ownership of an [`AdiLed`] and redraws its pattern on the async runtime
+//! at a fixed rate, and the pattern can be changed at any time through the returned handle.
+//! This makes it easy to show the driver what the robot is doing, such as whether it is holding a game object.
+//!
+//! Example of showing the alliance color until the robot picks up a game object:
+//! ```rust
+//! let led = AdiLed::new(peripherals.adi_h, 30)?;
+//! let animation = LedAnimation::spawn(
+//!     led,
+//!     LedPattern::Alliance(Alliance::Red),
+//!     Duration::from_millis(20),
+//! );
+//!
+//! loop {
+//!     if intake_switch.is_high()? {
+//!         animation.set_pattern(LedPattern::Blink {
+//!             on: Rgb::new(0, 128, 0),
+//!             off: Rgb::BLACK,
+//!             period: Duration::from_millis(250),
+//!         });
+//!     }
+//!     sleep(Duration::from_millis(10)).await;
+//! }
+//! ```
+
+use alloc::{sync::Arc, vec};
+use core::time::Duration;
+
+use super::AdiLed;
+use crate::{async_runtime, sensors::vision::Rgb, sync::Mutex, task::sleep};
+
+/// An alliance in a match.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Alliance {
+    Red,
+    Blue,
+}
+
+impl Alliance {
+    /// Returns the color used to show the alliance on an LED strip.
+    pub const fn color(self) -> Rgb {
+        match self {
+            Self::Red => Rgb::new(128, 0, 0),
+            Self::Blue => Rgb::new(0, 0, 128),
+        }
+    }
+}
+
+/// A pattern that can be shown on an LED strip.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum LedPattern {
+    /// Every pixel is the same color.
+    Solid(Rgb),
+    /// The color fades from `start` at the first pixel to `end` at the last pixel.
+    Gradient { start: Rgb, end: Rgb },
+    /// Every color of the rainbow spread along the strip, moving one full strip length every `period`.
+    Rainbow { period: Duration },
+    /// A group of `length` pixels of `color` that moves along a `background` one pixel every `step`,
+    /// wrapping around at the end of the strip.
+    Chase {
+        color: Rgb,
+        background: Rgb,
+        length: usize,
+        step: Duration,
+    },
+    /// The whole strip switches between `on` and `off`, spending half of every `period` in each.
+    Blink { on: Rgb, off: Rgb, period: Duration },
+    /// Every pixel is the color of an alliance.
+    Alliance(Alliance),
+}
+
+impl LedPattern {
+    /// Draws the pattern as it looks `elapsed` after it started.
+    pub fn render(&self, elapsed: Duration, pixels: &mut [Rgb]) {
+        let len = pixels.len();
+        let elapsed_ms = elapsed.as_millis() as u64;
+        match *self {
+            Self::Solid(color) => pixels.fill(color),
+            Self::Gradient { start, end } => {
+                for (index, pixel) in pixels.iter_mut().enumerate() {
+                    let t = if len > 1 {
+                        index as f64 / (len - 1) as f64
+                    } else {
+                        0.0
+                    };
+                    *pixel = lerp(start, end, t);
+                }
+            }
+            Self::Rainbow { period } => {
+                let offset = fraction(elapsed_ms, period);
+                for (index, pixel) in pixels.iter_mut().enumerate() {
+                    let hue = offset + index as f64 / len as f64;
+                    *pixel = hue_to_rgb(hue - libm::floor(hue));
+                }
+            }
+            Self::Chase {
+                color,
+                background,
+                length,
+                step,
+            } => {
+                pixels.fill(background);
+                if len == 0 {
+                    return;
+                }
+                let head = (elapsed_ms / (step.as_millis() as u64).max(1)) as usize % len;
+                for offset in 0..length.min(len) {
+                    pixels[(head + offset) % len] = color;
+                }
+            }
+            Self::Blink { on, off, period } => {
+                let color = if fraction(elapsed_ms, period) < 0.5 {
+                    on
+                } else {
+                    off
+                };
+                pixels.fill(color);
+            }
+            Self::Alliance(alliance) => pixels.fill(alliance.color()),
+        }
+    }
+}
+
+/// Returns how far through the current `period` the time `elapsed_ms` is, from 0 to 1.
+fn fraction(elapsed_ms: u64, period: Duration) -> f64 {
+    let period_ms = (period.as_millis() as u64).max(1);
+    (elapsed_ms % period_ms) as f64 / period_ms as f64
+}
+
+/// Blends linearly between two colors, where `t` is 0 at `from` and 1 at `to`.
+fn lerp(from: Rgb, to: Rgb, t: f64) -> Rgb {
+    let channel = |from: u8, to: u8| (from as f64 + (to as f64 - from as f64) * t) as u8;
+    Rgb::new(
+        channel(from.r, to.r),
+        channel(from.g, to.g),
+        channel(from.b, to.b),
+    )
+}
+
+/// Converts a hue from 0 to 1 into a fully saturated color at half brightness.
+fn hue_to_rgb(hue: f64) -> Rgb {
+    const BRIGHTNESS: f64 = 128.0;
+    let sector = hue * 6.0;
+    let rising = sector - libm::floor(sector);
+    let falling = 1.0 - rising;
+    let (r, g, b) = match sector as u8 {
+        0 => (1.0, rising, 0.0),
+        1 => (falling, 1.0, 0.0),
+        2 => (0.0, 1.0, rising),
+        3 => (0.0, falling, 1.0),
+        4 => (rising, 0.0, 1.0),
+        _ => (1.0, 0.0, falling),
+    };
+    Rgb::new(
+        (r * BRIGHTNESS) as u8,
+        (g * BRIGHTNESS) as u8,
+        (b * BRIGHTNESS) as u8,
+    )
+}
+
+struct AnimationState {
+    pattern: LedPattern,
+    /// The time the current pattern started, in milliseconds.
+    started: u32,
+    stopped: bool,
+}
+
+/// A handle to an animation running on an LED strip.
+///
+/// Handles are cheap to clone and can be shared between tasks.
+#[derive(Clone)]
+pub struct LedAnimation {
+    state: Arc<Mutex<AnimationState>>,
+}
+
+impl LedAnimation {
+    /// Starts showing `pattern` on a strip, redrawing it every `frame_interval`.
+    ///
+    /// The animation runs on the async runtime, so it only updates while the runtime is being polled.
+    /// It stops and turns off the strip once [`stop`](Self::stop) is called or every handle is dropped.
+    pub fn spawn(mut led: AdiLed, pattern: LedPattern, frame_interval: Duration) -> Self {
+        let animation = Self {
+            state: Arc::new(Mutex::new(AnimationState {
+                pattern,
+                started: unsafe { pros_sys::millis() },
+                stopped: false,
+            })),
+        };
+
+        // The task only holds a weak reference so that it stops once every handle is dropped.
+        let state = Arc::downgrade(&animation.state);
+        async_runtime::spawn(async move {
+            let mut frame = vec![Rgb::BLACK; led.len()];
+            loop {
+                {
+                    let Some(shared) = state.upgrade() else {
+                        break;
+                    };
+                    let state = shared.lock();
+                    if state.stopped {
+                        break;
+                    }
+                    let elapsed = unsafe { pros_sys::millis() }.wrapping_sub(state.started);
+                    state
+                        .pattern
+                        .render(Duration::from_millis(elapsed as u64), &mut frame);
+                }
+                // The strip may be unplugged, in which case the next frame is tried anyway.
+                _ = led.set_buffer(&frame);
+                sleep(frame_interval).await;
+            }
+            _ = led.clear();
+        })
+        .detach();
+
+        animation
+    }
+
+    /// Switches to a new pattern, which starts from the beginning.
+    pub fn set_pattern(&self, pattern: LedPattern) {
+        let mut state = self.state.lock();
+        state.pattern = pattern;
+        state.started = unsafe { pros_sys::millis() };
+    }
+
+    /// Returns the pattern currently being shown.
+    pub fn pattern(&self) -> LedPattern {
+        self.state.lock().pattern
+    }
+
+    /// Stops the animation and turns off the strip.
+    pub fn stop(&self) {
+        self.state.lock().stopped = true;
+    }
+}
@@ -1,8 +1,42 @@
+//! ADI (Three-Wire Port) devices.
+//!
+//! The brain has eight three-wire ports, labelled A to H, for older sensors and simple devices
+//! such as limit switches, bumpers, potentiometers and line trackers.
+//! Each device takes ownership of an [`AdiPort`], which can be obtained from [`Peripherals`](crate::peripherals::Peripherals).
+//!
+//! Currently supported devices are:
+//! - [`Analog inputs`](analog::AdiAnalogIn) (line trackers, light sensors and potentiometers)
+//! - [`Digital inputs`](digital::AdiDigitalIn) (limit switches and bumpers)
+//! - [`Digital outputs`](digital::AdiDigitalOut) (pneumatic solenoids)
+//! - [`Encoders`](encoder::AdiEncoder)
+//! - [`Potentiometers`](potentiometer::AdiPotentiometer)
+//! - [`Legacy gyroscopes`](gyro::AdiGyro)
+//! - [`Ultrasonic rangefinders`](ultrasonic::AdiUltrasonic)
+//! - [`Addressable LED strips`](led::AdiLed)
+
 use core::{
     ffi::c_int,
     ops::{Deref, DerefMut},
 };
 
+use pros_sys::{adi_port_config_e_t, PROS_ERR};
+use snafu::Snafu;
+
+use crate::error::{bail_on, map_errno, PortError};
+
+pub mod analog;
+pub mod digital;
+pub mod encoder;
+pub mod gyro;
+pub mod led;
+pub mod potentiometer;
+pub mod ultrasonic;
+
+/// A three-wire port on the brain, from 1 (A) to 8 (H).
+///
+/// Each port can only be used by one device, so ADI device constructors take ownership of an `AdiPort`.
+/// Every port can be obtained once from [`Peripherals::take`](crate::peripherals::Peripherals::take).
+#[derive(Debug, PartialEq, Eq, Hash)]
 pub struct AdiPort(u8);
 
 impl AdiPort {
@@ -10,25 +44,38 @@ impl AdiPort {
     ///
     /// # Safety
     ///
-    /// The port must be above 0 and below [`pros_sys::NUM_ADI_PORTS`].
-    pub unsafe fn new_unchecked(port: u8) -> Self {
+    /// No other device may be using the port,
+    /// and the port must be between 1 and [`pros_sys::NUM_ADI_PORTS`].
+    pub const unsafe fn new_unchecked(port: u8) -> Self {
         Self(port)
     }
-    /// Create an AdiPort, returning `None` if the port is invalid.
-    pub fn try_new(port: u8) -> Option<Self> {
-        if c_int::from(port) < pros_sys::NUM_ADI_PORTS {
-            Some(Self(port))
-        } else {
-            None
-        }
-    }
-    /// Create an AdiPort.
+    /// Create an AdiPort without taking it from [`Peripherals`](crate::peripherals::Peripherals).
+    ///
+    /// # Safety
+    ///
+    /// No other device may be using the port.
     ///
     /// # Panics
     ///
-    /// Panics if the port is greater than or equal to [`pros_sys::NUM_ADI_PORTS`].
-    pub fn new(port: u8) -> Self {
-        Self::try_new(port).expect("Invalid ADI port")
+    /// Panics if the port is not between 1 and [`pros_sys::NUM_ADI_PORTS`].
+    pub unsafe fn new(port: u8) -> Self {
+        assert!(
+            (1..=pros_sys::NUM_ADI_PORTS).contains(&c_int::from(port)),
+            "Invalid ADI port"
+        );
+        Self(port)
+    }
+    /// Returns the number of the port, from 1 (A) to 8 (H).
+    pub const fn index(&self) -> u8 {
+        self.0
+    }
+
+    /// Configures the port to act as the given type of device.
+    pub(crate) fn configure(&self, config: adi_port_config_e_t) -> Result<(), AdiError> {
+        unsafe {
+            bail_on!(PROS_ERR, pros_sys::adi_port_set_config(self.0, config));
+        }
+        Ok(())
     }
 }
 
@@ -45,12 +92,36 @@ impl DerefMut for AdiPort {
     }
 }
 
-pub struct AdiAnalogIn {
-    port: AdiPort,
+#[derive(Debug, Snafu)]
+pub enum AdiError {
+    #[snafu(display("The port is not configured for this kind of device."))]
+    WrongMode,
+    #[snafu(display("The sensor must be calibrated before reading calibrated values."))]
+    NotCalibrated,
+    #[snafu(display(
+        "Ports {first} and {second} are not a pair of neighboring ports (A and B, C and D, E and F, or G and H)."
+    ))]
+    InvalidPortPair { first: u8, second: u8 },
+    #[snafu(display(
+        "LED strips must have between 1 and 64 pixels, but {length} were requested."
+    ))]
+    InvalidLedLength { length: usize },
+    #[snafu(display(
+        "Pixel {index} is past the end of the LED strip, which has {length} pixels."
+    ))]
+    PixelOutOfRange { index: usize, length: usize },
+    #[snafu(display(
+        "The device was given an invalid argument, such as an LED strip buffer it can't use."
+    ))]
+    InvalidArgument,
+    #[snafu(display("{source}"), context(false))]
+    Port { source: PortError },
 }
 
-impl AdiAnalogIn {
-    pub fn new(port: AdiPort) -> Self {
-        Self { port }
+map_errno! {
+    AdiError {
+        EADDRINUSE => Self::WrongMode,
+        EINVAL => Self::InvalidArgument,
     }
+    inherit PortError;
 }
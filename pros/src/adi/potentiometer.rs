@@ -0,0 +1,92 @@
+//! ADI potentiometers.
+//!
+//! Potentiometers measure the absolute angle of a shaft, which makes them useful for mechanisms
+//! like arms and lifts that only move through part of a rotation.
+//! The original (EDR) potentiometer turns through 250 degrees, while the V2 potentiometer turns through 330 degrees.
+//!
+//! Example of holding an arm at 90 degrees:
+//! ```rust
+//! let potentiometer = AdiPotentiometer::new(peripherals.adi_a, AdiPotentiometerType::V2)?;
+//! let mut pid = PidController::new(0.05, 0.0, 0.0);
+//! loop {
+//!     let angle = potentiometer.angle()?.into_degrees() as f32;
+//!     arm.set_output(pid.update(90.0, angle))?;
+//!     sleep(Duration::from_millis(10)).await;
+//! }
+//! ```
+
+use pros_sys::{adi_potentiometer_t, adi_potentiometer_type_e_t, PROS_ERR, PROS_ERR_F};
+
+use super::{AdiError, AdiPort};
+use crate::{error::bail_on, position::Position};
+
+/// The version of a potentiometer.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum AdiPotentiometerType {
+    /// The original potentiometer, which turns through 250 degrees.
+    Edr,
+    /// The V2 potentiometer, which turns through 330 degrees.
+    V2,
+}
+
+impl AdiPotentiometerType {
+    /// Returns the largest angle the potentiometer can read, in degrees.
+    pub const fn max_angle(self) -> f64 {
+        match self {
+            Self::Edr => 250.0,
+            Self::V2 => 330.0,
+        }
+    }
+}
+
+impl From<AdiPotentiometerType> for adi_potentiometer_type_e_t {
+    fn from(value: AdiPotentiometerType) -> Self {
+        match value {
+            AdiPotentiometerType::Edr => pros_sys::E_ADI_POT_EDR,
+            AdiPotentiometerType::V2 => pros_sys::E_ADI_POT_V2,
+        }
+    }
+}
+
+/// A potentiometer plugged into a three-wire port.
+#[derive(Debug)]
+pub struct AdiPotentiometer {
+    raw: adi_potentiometer_t,
+    port: AdiPort,
+    potentiometer_type: AdiPotentiometerType,
+}
+
+impl AdiPotentiometer {
+    /// Configures a three-wire port as a potentiometer of the given type.
+    pub fn new(port: AdiPort, potentiometer_type: AdiPotentiometerType) -> Result<Self, AdiError> {
+        let raw = unsafe {
+            bail_on!(
+                PROS_ERR,
+                pros_sys::adi_potentiometer_type_init(port.index(), potentiometer_type.into())
+            )
+        };
+        Ok(Self {
+            raw,
+            port,
+            potentiometer_type,
+        })
+    }
+
+    /// Returns the port the potentiometer is plugged into.
+    pub fn port(&self) -> &AdiPort {
+        &self.port
+    }
+
+    /// Returns the version of the potentiometer.
+    pub fn potentiometer_type(&self) -> AdiPotentiometerType {
+        self.potentiometer_type
+    }
+
+    /// Returns the angle of the potentiometer,
+    /// from 0 to the [maximum angle](AdiPotentiometerType::max_angle) of its type.
+    pub fn angle(&self) -> Result<Position, AdiError> {
+        let degrees =
+            unsafe { bail_on!(PROS_ERR_F, pros_sys::adi_potentiometer_get_angle(self.raw)) };
+        Ok(Position::from_degrees(degrees))
+    }
+}
@@ -0,0 +1,83 @@
+//! ADI ultrasonic rangefinders.
+//!
+//! The legacy ultrasonic rangefinder sends out a pulse of sound and times how long it takes to echo back.
+//! It uses two three-wire ports, which must be a pair of neighboring ports:
+//! A and B, C and D, E and F, or G and H.
+//!
+//! Example of stopping when a wall is close:
+//! ```rust
+//! let ultrasonic = AdiUltrasonic::new(peripherals.adi_c, peripherals.adi_d)?;
+//! motor.set_output(0.5)?;
+//! while ultrasonic.distance()?.map_or(true, |distance| distance > 200.0) {
+//!     sleep(Duration::from_millis(20)).await;
+//! }
+//! motor.brake()?;
+//! ```
+
+use pros_sys::{adi_ultrasonic_t, PROS_ERR};
+
+use super::{AdiError, AdiPort};
+use crate::{error::bail_on, sensors::RangeFinder};
+
+/// An ultrasonic rangefinder plugged into two three-wire ports.
+///
+/// The rangefinder stops sending pulses when it is dropped.
+#[derive(Debug)]
+pub struct AdiUltrasonic {
+    raw: adi_ultrasonic_t,
+    ping: AdiPort,
+    echo: AdiPort,
+}
+
+impl AdiUltrasonic {
+    /// Configures two three-wire ports as an ultrasonic rangefinder.
+    ///
+    /// `ping` is the port the orange output cable is plugged into,
+    /// and `echo` is the port the yellow input cable is plugged into.
+    /// `ping` must be an odd port (A, C, E or G) and `echo` must be the port right after it.
+    pub fn new(ping: AdiPort, echo: AdiPort) -> Result<Self, AdiError> {
+        if ping.index() % 2 == 0 || echo.index() != ping.index() + 1 {
+            return Err(AdiError::InvalidPortPair {
+                first: ping.index(),
+                second: echo.index(),
+            });
+        }
+        let raw = unsafe {
+            bail_on!(
+                PROS_ERR,
+                pros_sys::adi_ultrasonic_init(ping.index(), echo.index())
+            )
+        };
+        Ok(Self { raw, ping, echo })
+    }
+
+    /// Returns the ports the ping and echo cables are plugged into.
+    pub fn ports(&self) -> (&AdiPort, &AdiPort) {
+        (&self.ping, &self.echo)
+    }
+
+    /// Returns the distance to the nearest object in millimeters,
+    /// or `None` if no object is in range.
+    pub fn distance(&self) -> Result<Option<f64>, AdiError> {
+        let raw = unsafe { bail_on!(PROS_ERR, pros_sys::adi_ultrasonic_get(self.raw)) };
+        // PROS reports the distance in tenths of a millimeter.
+        Ok((raw > 0).then(|| raw as f64 / 10.0))
+    }
+}
+
+impl RangeFinder for AdiUltrasonic {
+    type Error = AdiError;
+
+    /// Returns the distance to the nearest object in millimeters, or infinity if no object is in range.
+    fn distance(&self) -> Result<f64, AdiError> {
+        Ok(AdiUltrasonic::distance(self)?.unwrap_or(f64::INFINITY))
+    }
+}
+
+impl Drop for AdiUltrasonic {
+    fn drop(&mut self) {
+        unsafe {
+            pros_sys::adi_ultrasonic_shutdown(self.raw);
+        }
+    }
+}
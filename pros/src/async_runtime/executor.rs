@@ -11,7 +11,7 @@ use alloc::{collections::VecDeque, sync::Arc};
 use async_task::{Runnable, Task};
 use waker_fn::waker_fn;
 
-use crate::{os_task_local, task::delay};
+use crate::{os_task_local, sync::Mutex, task::delay};
 
 use super::reactor::Reactor;
 
@@ -20,7 +20,11 @@ os_task_local! {
 }
 
 pub(crate) struct Executor {
-    queue: RefCell<VecDeque<Runnable>>,
+    /// Tasks that are ready to be polled.
+    ///
+    /// This is behind a lock rather than a `RefCell` because wakers can be woken from other FreeRTOS tasks.
+    /// The lock is a FreeRTOS mutex, so a lower priority task holding it is boosted instead of being starved.
+    queue: Mutex<VecDeque<Runnable>>,
     pub(crate) reactor: RefCell<Reactor>,
 }
 
@@ -30,19 +34,17 @@ impl !Sync for Executor {}
 impl Executor {
     pub fn new() -> Self {
         Self {
-            queue: RefCell::new(VecDeque::new()),
+            queue: Mutex::new(VecDeque::new()),
             reactor: RefCell::new(Reactor::new()),
         }
     }
 
     pub fn spawn<T>(&'static self, future: impl Future<Output = T> + 'static) -> Task<T> {
-        // SAFETY: `runnable` will never be moved off this thread or shared with another thread because of the `!Send + !Sync` bounds on `Self`.
+        // SAFETY: `runnable` is only ever run on this thread because of the `!Send + !Sync` bounds on `Self`.
+        //         Wakers from other threads only push it onto the queue, which is behind a lock.
         //         Both `future` and `schedule` are `'static` so they cannot be used after being freed.
-        //   TODO: Make sure that the waker can never be sent off the thread.
         let (runnable, task) = unsafe {
-            async_task::spawn_unchecked(future, |runnable| {
-                self.queue.borrow_mut().push_back(runnable)
-            })
+            async_task::spawn_unchecked(future, |runnable| self.queue.lock().push_back(runnable))
         };
 
         runnable.schedule();
@@ -53,10 +55,7 @@ impl Executor {
     pub(crate) fn tick(&self) -> bool {
         self.reactor.borrow_mut().tick();
 
-        let runnable = {
-            let mut queue = self.queue.borrow_mut();
-            queue.pop_front()
-        };
+        let runnable = self.queue.lock().pop_front();
         match runnable {
             Some(runnable) => {
                 runnable.run();
@@ -20,6 +20,30 @@ impl CompetitionStatus {
     }
 }
 
+/// The part of a match the robot is currently in.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CompetitionMode {
+    /// The robot is disabled and cannot move.
+    Disabled,
+    /// The robot is running autonomously.
+    Autonomous,
+    /// The robot is being controlled by a driver.
+    Opcontrol,
+}
+
+impl CompetitionStatus {
+    /// Get the mode the robot was in when this status was created.
+    pub const fn mode(&self) -> CompetitionMode {
+        if self.disabled() {
+            CompetitionMode::Disabled
+        } else if self.autonomous() {
+            CompetitionMode::Autonomous
+        } else {
+            CompetitionMode::Opcontrol
+        }
+    }
+}
+
 /// Get the current status of the robot.
 pub fn get_status() -> CompetitionStatus {
     CompetitionStatus(unsafe { pros_sys::misc::competition_get_status() })
@@ -39,3 +63,8 @@ pub fn is_disabled() -> bool {
 pub fn is_connected() -> bool {
     unsafe { pros_sys::misc::competition_is_connected() }
 }
+
+/// Get the mode the robot is currently in.
+pub fn get_mode() -> CompetitionMode {
+    get_status().mode()
+}
@@ -223,12 +223,31 @@ impl Controller {
     pub fn joystick_axis(&self, axis: JoystickAxis) -> f32 {
         unsafe { pros_sys::controller_get_analog(self.id(), axis as u32) as f32 / 127.0 }
     }
+
+    /// Rumbles the controller.
+    ///
+    /// The pattern can be up to 8 characters long, where `.` is a short rumble,
+    /// `-` is a long rumble and ` ` is a pause.
+    pub fn rumble(&self, pattern: &str) -> Result<(), ControllerError> {
+        if pattern.len() > 8 || !pattern.chars().all(|c| matches!(c, '.' | '-' | ' ')) {
+            return Err(ControllerError::InvalidRumblePattern);
+        }
+        let c_pattern = CString::new(pattern).unwrap();
+        bail_on!(PROS_ERR, unsafe {
+            pros_sys::controller_rumble(self.id(), c_pattern.as_ptr())
+        });
+        Ok(())
+    }
 }
 
 #[derive(Debug, Snafu)]
 pub enum ControllerError {
     #[snafu(display("Another resource is already using the controller"))]
     ConcurrentAccess,
+    #[snafu(display(
+        "Rumble patterns can only contain '.', '-' and ' ', and can be at most 8 characters long."
+    ))]
+    InvalidRumblePattern,
 }
 
 map_errno! {
@@ -0,0 +1,229 @@
+//! Information about the devices plugged into the brain's smart ports.
+//!
+//! The brain keeps a registry of what is plugged into each of its 21 smart ports.
+//! [`plugged_type`] reports the device that is physically plugged into a port,
+//! while [`bound_type`] reports the device PROS has configured the port for.
+//!
+//! Device constructors such as [`Motor::new`](crate::motor::Motor::new) check the registry
+//! and return [`PortError::WrongDevice`] if a different kind of device is plugged into their port.
+//! Empty ports are allowed so that devices can be plugged in after they are created.
+//!
+//! Device constructors take a [`SmartPort`] rather than a port number, so that two devices can't
+//! be created on the same port. Ports are handed out by [`Peripherals`](crate::peripherals::Peripherals).
+//!
+//! Example of printing everything plugged into the brain:
+//! ```rust
+//! for (port, device) in (1..).zip(plugged_devices()) {
+//!     if device.is_connected() {
+//!         println!("Port {port}: {device:?}");
+//!     }
+//! }
+//! ```
+
+pub mod monitor;
+
+use pros_sys::{apix::*, PROS_ERR};
+use snafu::Snafu;
+
+use crate::error::{bail_on, map_errno, PortError};
+
+/// The number of smart ports on the brain.
+pub const NUM_SMART_PORTS: u8 = 21;
+
+/// A kind of device that can be plugged into a smart port.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum DeviceType {
+    /// Nothing is plugged in.
+    None,
+    Motor,
+    Rotation,
+    Imu,
+    Distance,
+    Radio,
+    Vision,
+    /// A three-wire expander.
+    Adi,
+    Optical,
+    Gps,
+    /// A port being used as a generic serial port.
+    Serial,
+    /// A device PROS does not know about.
+    Undefined,
+}
+
+impl DeviceType {
+    /// Returns whether this is an actual device rather than an empty port.
+    pub const fn is_connected(self) -> bool {
+        !matches!(self, Self::None)
+    }
+}
+
+impl From<v5_device_e_t> for DeviceType {
+    fn from(value: v5_device_e_t) -> Self {
+        match value {
+            E_DEVICE_NONE => Self::None,
+            E_DEVICE_MOTOR => Self::Motor,
+            E_DEVICE_ROTATION => Self::Rotation,
+            E_DEVICE_IMU => Self::Imu,
+            E_DEVICE_DISTANCE => Self::Distance,
+            E_DEVICE_RADIO => Self::Radio,
+            E_DEVICE_VISION => Self::Vision,
+            E_DEVICE_ADI => Self::Adi,
+            E_DEVICE_OPTICAL => Self::Optical,
+            E_DEVICE_GPS => Self::Gps,
+            E_DEVICE_SERIAL => Self::Serial,
+            _ => Self::Undefined,
+        }
+    }
+}
+
+impl From<DeviceType> for v5_device_e_t {
+    fn from(value: DeviceType) -> Self {
+        match value {
+            DeviceType::None => E_DEVICE_NONE,
+            DeviceType::Motor => E_DEVICE_MOTOR,
+            DeviceType::Rotation => E_DEVICE_ROTATION,
+            DeviceType::Imu => E_DEVICE_IMU,
+            DeviceType::Distance => E_DEVICE_DISTANCE,
+            DeviceType::Radio => E_DEVICE_RADIO,
+            DeviceType::Vision => E_DEVICE_VISION,
+            DeviceType::Adi => E_DEVICE_ADI,
+            DeviceType::Optical => E_DEVICE_OPTICAL,
+            DeviceType::Gps => E_DEVICE_GPS,
+            DeviceType::Serial => E_DEVICE_SERIAL,
+            DeviceType::Undefined => E_DEVICE_UNDEFINED,
+        }
+    }
+}
+
+/// A smart port on the brain.
+///
+/// Each port can only be used by one device, so device constructors take ownership of a `SmartPort`.
+/// Every port can be obtained once from [`Peripherals::take`](crate::peripherals::Peripherals::take).
+#[derive(Debug, PartialEq, Eq, Hash)]
+pub struct SmartPort {
+    index: u8,
+}
+
+impl SmartPort {
+    /// Creates a smart port without taking it from [`Peripherals`](crate::peripherals::Peripherals).
+    ///
+    /// # Safety
+    ///
+    /// No other device may be using the port, and `index` must be between 1 and [`NUM_SMART_PORTS`].
+    pub const unsafe fn new(index: u8) -> Self {
+        Self { index }
+    }
+
+    /// Returns the number of the port, from 1 to 21.
+    pub const fn index(&self) -> u8 {
+        self.index
+    }
+
+    /// Returns the type of device physically plugged into the port.
+    pub fn plugged_type(&self) -> Result<DeviceType, PortError> {
+        plugged_type(self.index)
+    }
+
+    /// Returns whether anything is plugged into the port.
+    pub fn connected(&self) -> bool {
+        self.plugged_type().is_ok_and(DeviceType::is_connected)
+    }
+}
+
+/// A device plugged into a smart port.
+///
+/// This is implemented by every smart port device, so code can check on devices without knowing what they are.
+pub trait SmartDevice {
+    /// Returns the number of the port the device is plugged into, from 1 to 21.
+    fn port_index(&self) -> u8;
+
+    /// Returns the type of device this is.
+    fn device_type(&self) -> DeviceType;
+
+    /// Returns whether the device is currently plugged into its port.
+    fn is_connected(&self) -> bool {
+        plugged_type(self.port_index()).is_ok_and(|found| found == self.device_type())
+    }
+}
+
+/// Converts a one-indexed smart port into the zero-indexed port the registry uses.
+fn registry_port(port: u8) -> Result<u8, PortError> {
+    if (1..=NUM_SMART_PORTS).contains(&port) {
+        Ok(port - 1)
+    } else {
+        Err(PortError::PortOutOfRange)
+    }
+}
+
+/// Returns the type of device physically plugged into a smart port.
+pub fn plugged_type(port: u8) -> Result<DeviceType, PortError> {
+    let port = registry_port(port)?;
+    Ok(unsafe { registry_get_plugged_type(port) }.into())
+}
+
+/// Returns the type of device PROS has configured a smart port for.
+///
+/// This is not necessarily what is plugged in; see [`plugged_type`].
+pub fn bound_type(port: u8) -> Result<DeviceType, PortError> {
+    let port = registry_port(port)?;
+    Ok(unsafe { registry_get_bound_type(port) }.into())
+}
+
+/// Returns the type of device plugged into every smart port, starting at port 1.
+pub fn plugged_devices() -> [DeviceType; NUM_SMART_PORTS as usize] {
+    core::array::from_fn(|index| unsafe { registry_get_plugged_type(index as u8) }.into())
+}
+
+/// Configures a smart port for the given type of device.
+///
+/// The device must already be plugged in. This is done automatically by device constructors,
+/// so it is only needed when talking to devices through [`pros_sys`] directly.
+pub fn bind_port(port: u8, device: DeviceType) -> Result<(), DeviceError> {
+    validate_port(port, device)?;
+    unsafe {
+        bail_on!(
+            PROS_ERR,
+            registry_bind_port(registry_port(port)?, device.into())
+        );
+    }
+    Ok(())
+}
+
+/// Removes the device configuration of a smart port, if it has one.
+pub fn unbind_port(port: u8) -> Result<(), DeviceError> {
+    unsafe {
+        bail_on!(PROS_ERR, registry_unbind_port(registry_port(port)?));
+    }
+    Ok(())
+}
+
+/// Checks that nothing other than the expected type of device is plugged into a smart port.
+///
+/// Empty ports pass the check.
+pub(crate) fn validate_port(port: u8, expected: DeviceType) -> Result<(), PortError> {
+    let found = plugged_type(port)?;
+    if found.is_connected() && found != expected {
+        return Err(PortError::WrongDevice {
+            port,
+            expected,
+            found,
+        });
+    }
+    Ok(())
+}
+
+#[derive(Debug, Snafu)]
+pub enum DeviceError {
+    #[snafu(display("The port is already configured for another device."))]
+    AlreadyBound,
+    #[snafu(display("{source}"), context(false))]
+    Port { source: PortError },
+}
+
+map_errno! {
+    DeviceError {
+        EADDRINUSE => Self::AlreadyBound,
+    }
+    inherit PortError;
+}
@@ -0,0 +1,276 @@
+//! Watching for devices being plugged in and unplugged.
+//!
+//! [`DeviceMonitor::spawn`] starts a task that checks the device registry at a fixed interval and
+//! publishes a [`DeviceEvent`] whenever a device is connected or disconnected.
+//!
+//! The monitor also checks every port that has been configured for a device (by creating that device).
+//! Reads from a device fail with [`PortError::PortCannotBeConfigured`](crate::error::PortError::PortCannotBeConfigured)
+//! whenever the device plugged into its port doesn't match what the port was configured for,
+//! so [`DeviceEvent::Unavailable`] and [`DeviceEvent::Available`] are sent when reads from a device start and stop failing.
+//!
+//! Any number of [`DeviceEvents`] subscriptions can be created from the monitor, and each one receives every event
+//! that happens after it was created. The monitoring task stops once every handle to the monitor has been dropped.
+//!
+//! Example of rumbling the controller when a device is unplugged:
+//! ```rust
+//! let monitor = DeviceMonitor::spawn(Duration::from_millis(50));
+//! let mut events = monitor.subscribe();
+//! spawn(async move {
+//!     loop {
+//!         if let DeviceEvent::Disconnected { port, device } = events.next().await {
+//!             println!("{device:?} on port {port} was unplugged!");
+//!             _ = Controller::Master.rumble("---");
+//!         }
+//!     }
+//! });
+//! ```
+
+use alloc::{
+    collections::VecDeque,
+    sync::{Arc, Weak},
+    vec::Vec,
+};
+use core::{
+    future::poll_fn,
+    sync::atomic::{AtomicBool, Ordering},
+    task::{Poll, Waker},
+    time::Duration,
+};
+
+use pros_sys::apix::{registry_get_bound_type, registry_get_plugged_type};
+
+use super::{DeviceType, NUM_SMART_PORTS};
+use crate::{
+    sync::Mutex,
+    task::{self, Interval},
+};
+
+/// The most events a subscription will hold before dropping the oldest ones.
+const MAX_QUEUED_EVENTS: usize = 32;
+
+/// A change in what is plugged into a smart port.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DeviceEvent {
+    /// A device was plugged in.
+    Connected { port: u8, device: DeviceType },
+    /// A device was unplugged.
+    Disconnected { port: u8, device: DeviceType },
+    /// Reads from a device started failing because its port no longer has the right kind of device plugged in.
+    Unavailable { port: u8, device: DeviceType },
+    /// Reads from a device that was [unavailable](DeviceEvent::Unavailable) work again.
+    Available { port: u8, device: DeviceType },
+}
+
+impl DeviceEvent {
+    /// Returns the smart port the event happened on.
+    pub const fn port(&self) -> u8 {
+        match self {
+            Self::Connected { port, .. }
+            | Self::Disconnected { port, .. }
+            | Self::Unavailable { port, .. }
+            | Self::Available { port, .. } => *port,
+        }
+    }
+
+    /// Returns the type of device that the event is about.
+    ///
+    /// For [`Unavailable`](DeviceEvent::Unavailable) and [`Available`](DeviceEvent::Available) events,
+    /// this is the type of device the port was configured for.
+    pub const fn device(&self) -> DeviceType {
+        match self {
+            Self::Connected { device, .. }
+            | Self::Disconnected { device, .. }
+            | Self::Unavailable { device, .. }
+            | Self::Available { device, .. } => *device,
+        }
+    }
+}
+
+struct EventQueue {
+    events: VecDeque<DeviceEvent>,
+    /// Woken when an event is pushed onto the queue.
+    waker: Option<Waker>,
+}
+
+/// What a single port had plugged in and configured at the last check.
+#[derive(Clone, Copy)]
+struct PortState {
+    plugged: DeviceType,
+    bound: DeviceType,
+}
+
+impl PortState {
+    fn read(index: u8) -> Self {
+        Self {
+            plugged: unsafe { registry_get_plugged_type(index) }.into(),
+            bound: unsafe { registry_get_bound_type(index) }.into(),
+        }
+    }
+
+    /// Returns whether reads from the device the port is configured for will succeed.
+    fn available(&self) -> bool {
+        self.plugged == self.bound
+    }
+}
+
+struct MonitorState {
+    ports: [PortState; NUM_SMART_PORTS as usize],
+    subscribers: Vec<Weak<Mutex<EventQueue>>>,
+}
+
+/// A handle to a task watching the smart ports for changes.
+///
+/// Handles are cheap to clone and can be shared between tasks.
+/// The task stops when every handle has been dropped.
+#[derive(Clone)]
+pub struct DeviceMonitor {
+    state: Arc<Mutex<MonitorState>>,
+    stopped: Arc<AtomicBool>,
+}
+
+impl DeviceMonitor {
+    /// Starts a new task that checks the smart ports every `interval`.
+    ///
+    /// Devices that are already plugged in when the monitor starts do not produce events.
+    pub fn spawn(interval: Duration) -> Self {
+        let monitor = Self {
+            state: Arc::new(Mutex::new(MonitorState {
+                ports: core::array::from_fn(|index| PortState::read(index as u8)),
+                subscribers: Vec::new(),
+            })),
+            stopped: Arc::new(AtomicBool::new(false)),
+        };
+
+        // The task only holds a weak reference so that dropping every handle stops it.
+        let state = Arc::downgrade(&monitor.state);
+        let stopped = monitor.stopped.clone();
+        task::Builder::new()
+            .name("device monitor")
+            .spawn(move || {
+                let mut timer = Interval::start();
+                while !stopped.load(Ordering::Relaxed) {
+                    let Some(state) = state.upgrade() else {
+                        break;
+                    };
+                    Self::poll(&state);
+                    drop(state);
+                    timer.delay(interval);
+                }
+            })
+            .expect("Failed to spawn device monitor task");
+
+        monitor
+    }
+
+    fn poll(state: &Mutex<MonitorState>) {
+        let ports: [PortState; NUM_SMART_PORTS as usize] =
+            core::array::from_fn(|index| PortState::read(index as u8));
+        let mut state = state.lock();
+
+        let mut events = Vec::new();
+        for (port, (old, new)) in (1..).zip(state.ports.iter().zip(&ports)) {
+            if old.plugged != new.plugged {
+                if old.plugged.is_connected() {
+                    events.push(DeviceEvent::Disconnected {
+                        port,
+                        device: old.plugged,
+                    });
+                }
+                if new.plugged.is_connected() {
+                    events.push(DeviceEvent::Connected {
+                        port,
+                        device: new.plugged,
+                    });
+                }
+            }
+
+            // Ports that were just configured (or unconfigured) don't send availability events.
+            if new.bound.is_connected() && old.bound == new.bound {
+                match (old.available(), new.available()) {
+                    (true, false) => events.push(DeviceEvent::Unavailable {
+                        port,
+                        device: new.bound,
+                    }),
+                    (false, true) => events.push(DeviceEvent::Available {
+                        port,
+                        device: new.bound,
+                    }),
+                    _ => {}
+                }
+            }
+        }
+        state.ports = ports;
+
+        if events.is_empty() {
+            return;
+        }
+        // Subscriptions that have been dropped are removed here.
+        state.subscribers.retain(|subscriber| {
+            let Some(queue) = subscriber.upgrade() else {
+                return false;
+            };
+            let mut queue = queue.lock();
+            for &event in &events {
+                if queue.events.len() == MAX_QUEUED_EVENTS {
+                    queue.events.pop_front();
+                }
+                queue.events.push_back(event);
+            }
+            if let Some(waker) = queue.waker.take() {
+                waker.wake();
+            }
+            true
+        });
+    }
+
+    /// Returns a new subscription to the monitor's events.
+    pub fn subscribe(&self) -> DeviceEvents {
+        let queue = Arc::new(Mutex::new(EventQueue {
+            events: VecDeque::new(),
+            waker: None,
+        }));
+        self.state.lock().subscribers.push(Arc::downgrade(&queue));
+        DeviceEvents { queue }
+    }
+
+    /// Returns the type of device plugged into every smart port as of the last check, starting at port 1.
+    pub fn devices(&self) -> [DeviceType; NUM_SMART_PORTS as usize] {
+        self.state.lock().ports.map(|port| port.plugged)
+    }
+
+    /// Stops the monitoring task.
+    ///
+    /// Existing subscriptions stay open but will not receive any more events.
+    pub fn stop(&self) {
+        self.stopped.store(true, Ordering::Relaxed);
+    }
+}
+
+/// A subscription to the events of a [`DeviceMonitor`].
+///
+/// Up to 32 events are kept until they are read, after which the oldest events are dropped.
+pub struct DeviceEvents {
+    queue: Arc<Mutex<EventQueue>>,
+}
+
+impl DeviceEvents {
+    /// Returns the oldest unread event, or `None` if there are no new events.
+    pub fn try_next(&mut self) -> Option<DeviceEvent> {
+        self.queue.lock().events.pop_front()
+    }
+
+    /// Waits for the next event.
+    pub async fn next(&mut self) -> DeviceEvent {
+        poll_fn(|cx| {
+            let mut queue = self.queue.lock();
+            match queue.events.pop_front() {
+                Some(event) => Poll::Ready(event),
+                None => {
+                    queue.waker = Some(cx.waker().clone());
+                    Poll::Pending
+                }
+            }
+        })
+        .await
+    }
+}
@@ -0,0 +1,168 @@
+//! Differential (tank) drivetrains.
+//!
+//! A differential drivetrain has a left and a right side of wheels that are each driven together.
+//! It turns by driving one side faster than the other.
+//!
+//! Example of driving with a controller:
+//! ```rust
+//! let drive = DifferentialDrive::new(
+//!     [
+//!         Motor::new(peripherals.port_1, BrakeMode::None)?,
+//!         Motor::new(peripherals.port_2, BrakeMode::None)?,
+//!     ],
+//!     [
+//!         Motor::new(peripherals.port_3, BrakeMode::None)?,
+//!         Motor::new(peripherals.port_4, BrakeMode::None)?,
+//!     ],
+//!     12.0,
+//!     3.25,
+//! );
+//!
+//! loop {
+//!     let state = controller.state();
+//!     drive.arcade(state.joysticks.left.y, state.joysticks.right.x)?;
+//!     sleep(Duration::from_millis(20)).await;
+//! }
+//! ```
+
+use core::f64::consts::PI;
+
+use alloc::vec::Vec;
+
+use super::DrivetrainError;
+use crate::motor::Motor;
+
+/// A drivetrain with a left and right side of motors.
+///
+/// Motors on the right side of the robot usually need to be [reversed](Motor::set_reversed)
+/// so that a positive output moves every wheel forward.
+pub struct DifferentialDrive {
+    left: Vec<Motor>,
+    right: Vec<Motor>,
+    track_width: f64,
+    wheel_diameter: f64,
+    gear_ratio: f64,
+}
+
+impl DifferentialDrive {
+    /// Creates a new differential drivetrain.
+    ///
+    /// `track_width` is the distance between the left and right wheels,
+    /// and `wheel_diameter` is the diameter of the driven wheels.
+    /// Both must be in the same units, which are used for every distance and velocity on this drivetrain.
+    pub fn new(
+        left: impl Into<Vec<Motor>>,
+        right: impl Into<Vec<Motor>>,
+        track_width: f64,
+        wheel_diameter: f64,
+    ) -> Self {
+        Self {
+            left: left.into(),
+            right: right.into(),
+            track_width,
+            wheel_diameter,
+            gear_ratio: 1.0,
+        }
+    }
+
+    /// Sets the number of wheel rotations per motor rotation.
+    ///
+    /// For example, a 36 tooth gear on the motors driving a 48 tooth gear on the wheels has a ratio of 0.75.
+    /// Directly driven wheels have a ratio of 1, which is the default.
+    pub fn set_gear_ratio(&mut self, gear_ratio: f64) {
+        self.gear_ratio = gear_ratio;
+    }
+
+    /// Returns the distance between the left and right wheels.
+    pub fn track_width(&self) -> f64 {
+        self.track_width
+    }
+
+    /// Returns the diameter of the driven wheels.
+    pub fn wheel_diameter(&self) -> f64 {
+        self.wheel_diameter
+    }
+
+    /// Returns the number of wheel rotations per motor rotation.
+    pub fn gear_ratio(&self) -> f64 {
+        self.gear_ratio
+    }
+
+    /// Sets the output of each side of the drivetrain from -1 to 1.
+    pub fn tank(&self, left: f32, right: f32) -> Result<(), DrivetrainError> {
+        for motor in &self.left {
+            motor.set_output(left)?;
+        }
+        for motor in &self.right {
+            motor.set_output(right)?;
+        }
+        Ok(())
+    }
+
+    /// Drives the robot forward while turning.
+    ///
+    /// `forward` is the forward velocity and `turn` is the angular velocity (clockwise is positive),
+    /// both from -1 to 1. If the combined output of a side exceeds 1, both sides are scaled down equally
+    /// so that the robot still follows the same curve.
+    pub fn arcade(&self, forward: f32, turn: f32) -> Result<(), DrivetrainError> {
+        let (left, right) = (forward + turn, forward - turn);
+        let max = left.abs().max(right.abs());
+        if max > 1.0 {
+            self.tank(left / max, right / max)
+        } else {
+            self.tank(left, right)
+        }
+    }
+
+    /// Sets the linear velocity of each side of the drivetrain.
+    ///
+    /// Velocities are in the drivetrain's distance units per second.
+    pub fn set_velocities(&self, left: f64, right: f64) -> Result<(), DrivetrainError> {
+        let (left, right) = (self.velocity_to_rpm(left), self.velocity_to_rpm(right));
+        for motor in &self.left {
+            motor.set_velocity(left)?;
+        }
+        for motor in &self.right {
+            motor.set_velocity(right)?;
+        }
+        Ok(())
+    }
+
+    /// Sets the voltage of each side of the drivetrain.
+    ///
+    /// Voltages outside of -12 to 12 volts are clamped.
+    pub fn set_voltages(&self, left: f32, right: f32) -> Result<(), DrivetrainError> {
+        let (left, right) = (left.clamp(-12.0, 12.0), right.clamp(-12.0, 12.0));
+        for motor in &self.left {
+            motor.set_voltage(left)?;
+        }
+        for motor in &self.right {
+            motor.set_voltage(right)?;
+        }
+        Ok(())
+    }
+
+    /// Returns the average distance travelled by the left and right sides since the motors were last zeroed.
+    pub fn distance(&self) -> Result<f64, DrivetrainError> {
+        let mut rotations = 0.0;
+        for motor in self.left.iter().chain(&self.right) {
+            rotations += motor.position()?.into_rotations();
+        }
+        let motor_count = (self.left.len() + self.right.len()).max(1) as f64;
+
+        Ok(rotations / motor_count * self.gear_ratio * PI * self.wheel_diameter)
+    }
+
+    /// Stops every motor based on its current [`BrakeMode`](crate::motor::BrakeMode).
+    pub fn brake(&self) -> Result<(), DrivetrainError> {
+        for motor in self.left.iter().chain(&self.right) {
+            motor.brake()?;
+        }
+        Ok(())
+    }
+
+    fn velocity_to_rpm(&self, velocity: f64) -> i32 {
+        let wheel_rpm = velocity / (PI * self.wheel_diameter) * 60.0;
+        (wheel_rpm / self.gear_ratio) as i32
+    }
+}
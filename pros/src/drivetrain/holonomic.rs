@@ -0,0 +1,211 @@
+//! Holonomic drivetrains.
+//!
+//! A holonomic drivetrain can move in any direction without turning first.
+//! X-drives and mecanum drives share the same wheel mixing as long as their wheels (or rollers)
+//! are angled at 45 degrees, so [`HolonomicDrive`] is used for both layouts.
+//! The mixing is done with [`MecanumKinematics`], and [`HolonomicDrive::set_chassis_speeds`]
+//! drives the robot at [`ChassisSpeeds`] from the [`kinematics`] module.
+//!
+//! Example of driving with a controller in field-centric mode:
+//! ```rust
+//! let mut drive = HolonomicDrive::new(front_left, front_right, back_left, back_right);
+//! drive.set_heading_source(Some(HeadingSource::Imu(imu)));
+//!
+//! loop {
+//!     let state = controller.state();
+//!     drive.drive(
+//!         state.joysticks.left.x,
+//!         state.joysticks.left.y,
+//!         state.joysticks.right.x,
+//!     )?;
+//!     sleep(Duration::from_millis(20)).await;
+//! }
+//! ```
+
+use super::{
+    kinematics::{self, ChassisSpeeds, MecanumKinematics, MecanumWheelSpeeds},
+    DrivetrainError, HeadingSource,
+};
+use crate::motor::Motor;
+
+/// Kinematics used to mix velocities from -1 to 1 into wheel outputs.
+///
+/// Its turn radius is 1, so turning at full speed gives every wheel a full output.
+const UNIT_KINEMATICS: MecanumKinematics = MecanumKinematics::new(1.0, 1.0);
+
+/// Outputs for each wheel of a holonomic drivetrain, from -1 to 1.
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub struct HolonomicWheelOutputs {
+    pub front_left: f32,
+    pub front_right: f32,
+    pub back_left: f32,
+    pub back_right: f32,
+}
+
+impl HolonomicWheelOutputs {
+    /// Mixes robot-relative velocities into outputs for each wheel.
+    ///
+    /// `x` is the strafing velocity (right is positive), `y` is the forward velocity,
+    /// and `turn` is the angular velocity (clockwise is positive). All three are from -1 to 1.
+    /// The resulting outputs are [desaturated](Self::desaturate).
+    pub fn from_velocities(x: f32, y: f32, turn: f32) -> Self {
+        Self::from_chassis_speeds(ChassisSpeeds::from_joystick(
+            x as f64,
+            y as f64,
+            turn as f64,
+        ))
+    }
+
+    /// Mixes chassis speeds, with every component from -1 to 1, into outputs for each wheel.
+    ///
+    /// The resulting outputs are [desaturated](Self::desaturate).
+    pub fn from_chassis_speeds(speeds: ChassisSpeeds) -> Self {
+        UNIT_KINEMATICS
+            .to_wheel_speeds(speeds)
+            .desaturate(1.0)
+            .into()
+    }
+
+    /// Scales the outputs down so that none of them exceed 1 while keeping their ratios the same.
+    ///
+    /// See [`kinematics::desaturate`].
+    pub fn desaturate(self) -> Self {
+        let mut outputs = [
+            self.front_left as f64,
+            self.front_right as f64,
+            self.back_left as f64,
+            self.back_right as f64,
+        ];
+        kinematics::desaturate(&mut outputs, 1.0);
+        let [front_left, front_right, back_left, back_right] = outputs;
+        Self {
+            front_left: front_left as f32,
+            front_right: front_right as f32,
+            back_left: back_left as f32,
+            back_right: back_right as f32,
+        }
+    }
+}
+
+impl From<MecanumWheelSpeeds> for HolonomicWheelOutputs {
+    fn from(speeds: MecanumWheelSpeeds) -> Self {
+        Self {
+            front_left: speeds.front_left as f32,
+            front_right: speeds.front_right as f32,
+            back_left: speeds.back_left as f32,
+            back_right: speeds.back_right as f32,
+        }
+    }
+}
+
+/// An X-drive or mecanum drivetrain with four motors.
+///
+/// Motors on the right side of the robot usually need to be [reversed](Motor::set_reversed)
+/// so that a positive output moves every wheel forward.
+pub struct HolonomicDrive {
+    front_left: Motor,
+    front_right: Motor,
+    back_left: Motor,
+    back_right: Motor,
+    heading_source: Option<HeadingSource>,
+}
+
+impl HolonomicDrive {
+    /// Creates a new robot-centric holonomic drivetrain from its four motors.
+    pub fn new(front_left: Motor, front_right: Motor, back_left: Motor, back_right: Motor) -> Self {
+        Self {
+            front_left,
+            front_right,
+            back_left,
+            back_right,
+            heading_source: None,
+        }
+    }
+
+    /// Sets the sensor used for field-centric control.
+    ///
+    /// When a heading source is set, [`HolonomicDrive::drive`] interprets its `x` and `y` velocities
+    /// relative to the field instead of the robot. Passing `None` switches back to robot-centric control.
+    pub fn set_heading_source(&mut self, heading_source: Option<HeadingSource>) {
+        self.heading_source = heading_source;
+    }
+
+    /// Returns true if the drivetrain is in field-centric mode.
+    pub fn field_centric(&self) -> bool {
+        self.heading_source.is_some()
+    }
+
+    /// Computes the wheel outputs for the given velocities without moving the motors.
+    ///
+    /// See [`HolonomicDrive::drive`] for a description of the parameters.
+    pub fn wheel_outputs(
+        &self,
+        x: f32,
+        y: f32,
+        turn: f32,
+    ) -> Result<HolonomicWheelOutputs, DrivetrainError> {
+        let mut speeds = ChassisSpeeds::from_joystick(x as f64, y as f64, turn as f64);
+        if let Some(source) = &self.heading_source {
+            // Heading sources measure degrees clockwise rather than radians counterclockwise.
+            let heading = -source.heading()?.to_radians();
+            speeds =
+                ChassisSpeeds::from_field_relative(speeds.vx, speeds.vy, speeds.omega, heading);
+        }
+
+        Ok(HolonomicWheelOutputs::from_chassis_speeds(speeds))
+    }
+
+    /// Drives the robot with the given velocities.
+    ///
+    /// `x` is the strafing velocity (right is positive), `y` is the forward velocity,
+    /// and `turn` is the angular velocity (clockwise is positive). All three are from -1 to 1.
+    /// In field-centric mode, `x` and `y` are relative to the direction the heading source was zeroed in.
+    pub fn drive(&self, x: f32, y: f32, turn: f32) -> Result<(), DrivetrainError> {
+        let outputs = self.wheel_outputs(x, y, turn)?;
+        self.set_wheel_outputs(outputs)
+    }
+
+    /// Drives the robot at chassis speeds in real units, such as ones from a motion controller.
+    ///
+    /// `max_wheel_speed` is the fastest a wheel can move in the same units as `kinematics`,
+    /// usually from [`kinematics::max_wheel_speed`], and must be positive and finite.
+    /// Speeds that are too fast for the wheels are desaturated.
+    /// Unlike [`HolonomicDrive::drive`], this is always robot-centric.
+    pub fn set_chassis_speeds(
+        &self,
+        kinematics: &MecanumKinematics,
+        speeds: ChassisSpeeds,
+        max_wheel_speed: f64,
+    ) -> Result<(), DrivetrainError> {
+        if !(max_wheel_speed.is_finite() && max_wheel_speed > 0.0) {
+            return Err(DrivetrainError::InvalidMaxWheelSpeed { max_wheel_speed });
+        }
+        let wheels = kinematics
+            .to_wheel_speeds(speeds)
+            .desaturate(max_wheel_speed);
+        self.set_wheel_outputs(HolonomicWheelOutputs::from(MecanumWheelSpeeds {
+            front_left: wheels.front_left / max_wheel_speed,
+            front_right: wheels.front_right / max_wheel_speed,
+            back_left: wheels.back_left / max_wheel_speed,
+            back_right: wheels.back_right / max_wheel_speed,
+        }))
+    }
+
+    /// Sets the output of each wheel directly.
+    pub fn set_wheel_outputs(&self, outputs: HolonomicWheelOutputs) -> Result<(), DrivetrainError> {
+        self.front_left.set_output(outputs.front_left)?;
+        self.front_right.set_output(outputs.front_right)?;
+        self.back_left.set_output(outputs.back_left)?;
+        self.back_right.set_output(outputs.back_right)?;
+        Ok(())
+    }
+
+    /// Stops every motor based on its current [`BrakeMode`](crate::motor::BrakeMode).
+    pub fn brake(&self) -> Result<(), DrivetrainError> {
+        self.front_left.brake()?;
+        self.front_right.brake()?;
+        self.back_left.brake()?;
+        self.back_right.brake()?;
+        Ok(())
+    }
+}
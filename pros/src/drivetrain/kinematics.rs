@@ -0,0 +1,353 @@
+//! Drivetrain kinematics.
+//!
+//! Kinematics models convert between the velocity of the whole robot ([`ChassisSpeeds`]) and the
+//! velocity of each of its wheels. Inverse kinematics (`to_wheel_speeds`) is used to drive the robot,
+//! while forward kinematics (`to_chassis_speeds`) is used to work out how the robot is moving from its encoders.
+//!
+//! Every model is a plain value with no hardware attached, so they can be used anywhere, including in tests.
+//!
+//! Coordinates follow the same convention as [`Pose`](crate::odometry::Pose):
+//! x is forwards, y is to the left, and counterclockwise rotation is positive.
+//! Joysticks (and [`HolonomicDrive`](super::holonomic::HolonomicDrive)) use x to the right, y forwards,
+//! and clockwise rotation instead, so [`ChassisSpeeds::from_joystick`] and [`ChassisSpeeds::to_joystick`]
+//! convert between the two.
+//! Wheel speeds are the linear speed of the wheel's surface in distance units per second.
+//! [`max_wheel_speed`] converts the maximum RPM of a motor's [`Gearset`] into the same units
+//! so that wheel speeds can be [desaturated](DifferentialWheelSpeeds::desaturate) before being sent to motors.
+//!
+//! Example of driving a mecanum drivetrain at a chassis velocity:
+//! ```rust
+//! let kinematics = MecanumKinematics::new(12.0, 10.0);
+//! let max_speed = max_wheel_speed(Gearset::Green, 4.0, 1.0);
+//! let wheels = kinematics
+//!     .to_wheel_speeds(ChassisSpeeds::new(30.0, 0.0, 1.0))
+//!     .desaturate(max_speed);
+//! ```
+
+use core::f64::consts::PI;
+
+use crate::{
+    motion::{wrap_radians, Waypoint},
+    motor::Gearset,
+};
+
+/// Returns the maximum surface speed of a wheel driven by a motor with the given gearset.
+///
+/// `gear_ratio` is the number of wheel rotations per motor rotation.
+/// The result is in units of `wheel_diameter` per second.
+pub fn max_wheel_speed(gearset: Gearset, wheel_diameter: f64, gear_ratio: f64) -> f64 {
+    gearset.max_rpm() / 60.0 * gear_ratio * PI * wheel_diameter
+}
+
+/// Scales every speed down so that none of them exceed `max_speed` while keeping their ratios the same.
+///
+/// Clamping each speed individually would change the direction the robot moves in,
+/// so all of the speeds are divided by the same factor instead.
+pub fn desaturate(speeds: &mut [f64], max_speed: f64) {
+    let max = speeds
+        .iter()
+        .fold(0.0_f64, |max, speed| max.max(speed.abs()));
+    if max > max_speed && max > 0.0 {
+        for speed in speeds {
+            *speed *= max_speed / max;
+        }
+    }
+}
+
+/// The velocity of a robot.
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub struct ChassisSpeeds {
+    /// Forward velocity in distance units per second.
+    pub vx: f64,
+    /// Leftward velocity in distance units per second.
+    pub vy: f64,
+    /// Angular velocity in radians per second (counterclockwise is positive).
+    pub omega: f64,
+}
+
+impl ChassisSpeeds {
+    /// Creates new robot-relative chassis speeds.
+    pub const fn new(vx: f64, vy: f64, omega: f64) -> Self {
+        Self { vx, vy, omega }
+    }
+
+    /// Creates chassis speeds from velocities in the joystick convention.
+    ///
+    /// `x` is the strafing velocity (right is positive), `y` is the forward velocity,
+    /// and `turn` is the angular velocity (clockwise is positive).
+    pub fn from_joystick(x: f64, y: f64, turn: f64) -> Self {
+        Self {
+            vx: y,
+            vy: -x,
+            omega: -turn,
+        }
+    }
+
+    /// Returns the chassis speeds as `(x, y, turn)` in the joystick convention.
+    ///
+    /// This is the reverse of [`ChassisSpeeds::from_joystick`].
+    pub fn to_joystick(self) -> (f64, f64, f64) {
+        (-self.vy, self.vx, -self.omega)
+    }
+
+    /// Converts field-relative velocities into robot-relative chassis speeds.
+    ///
+    /// `heading` is the robot's heading in radians counterclockwise from the field's x axis.
+    pub fn from_field_relative(vx: f64, vy: f64, omega: f64, heading: f64) -> Self {
+        let (sin, cos) = (libm::sin(heading), libm::cos(heading));
+        Self {
+            vx: vx * cos + vy * sin,
+            vy: -vx * sin + vy * cos,
+            omega,
+        }
+    }
+}
+
+/// Wheel speeds of a differential drivetrain.
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub struct DifferentialWheelSpeeds {
+    pub left: f64,
+    pub right: f64,
+}
+
+impl DifferentialWheelSpeeds {
+    /// Scales the speeds down so that neither exceeds `max_speed`. See [`desaturate`].
+    pub fn desaturate(self, max_speed: f64) -> Self {
+        let mut speeds = [self.left, self.right];
+        desaturate(&mut speeds, max_speed);
+        Self {
+            left: speeds[0],
+            right: speeds[1],
+        }
+    }
+}
+
+/// Kinematics of a differential (tank) drivetrain.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct DifferentialKinematics {
+    /// Distance between the left and right wheels.
+    pub track_width: f64,
+}
+
+impl DifferentialKinematics {
+    /// Creates a new differential kinematics model.
+    pub const fn new(track_width: f64) -> Self {
+        Self { track_width }
+    }
+
+    /// Returns the wheel speeds needed to drive at the given chassis speeds.
+    ///
+    /// Differential drivetrains can't strafe, so [`ChassisSpeeds::vy`] is ignored.
+    pub fn to_wheel_speeds(&self, speeds: ChassisSpeeds) -> DifferentialWheelSpeeds {
+        let turn = speeds.omega * self.track_width / 2.0;
+        DifferentialWheelSpeeds {
+            left: speeds.vx - turn,
+            right: speeds.vx + turn,
+        }
+    }
+
+    /// Returns the chassis speeds produced by the given wheel speeds.
+    pub fn to_chassis_speeds(&self, wheels: DifferentialWheelSpeeds) -> ChassisSpeeds {
+        ChassisSpeeds {
+            vx: (wheels.left + wheels.right) / 2.0,
+            vy: 0.0,
+            omega: (wheels.right - wheels.left) / self.track_width,
+        }
+    }
+}
+
+/// Wheel speeds of a four wheel holonomic drivetrain.
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub struct MecanumWheelSpeeds {
+    pub front_left: f64,
+    pub front_right: f64,
+    pub back_left: f64,
+    pub back_right: f64,
+}
+
+impl MecanumWheelSpeeds {
+    /// Scales the speeds down so that none of them exceed `max_speed`. See [`desaturate`].
+    pub fn desaturate(self, max_speed: f64) -> Self {
+        let mut speeds = [
+            self.front_left,
+            self.front_right,
+            self.back_left,
+            self.back_right,
+        ];
+        desaturate(&mut speeds, max_speed);
+        let [front_left, front_right, back_left, back_right] = speeds;
+        Self {
+            front_left,
+            front_right,
+            back_left,
+            back_right,
+        }
+    }
+}
+
+/// Kinematics of a mecanum drivetrain or X-drive.
+///
+/// For an X-drive, the wheel speeds are the component of each wheel's velocity along the robot's
+/// x axis, so the actual surface speed of each omni wheel is larger by a factor of √2.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct MecanumKinematics {
+    /// Distance between the left and right wheels.
+    pub track_width: f64,
+    /// Distance between the front and back wheels.
+    pub wheelbase: f64,
+}
+
+impl MecanumKinematics {
+    /// Creates a new mecanum kinematics model.
+    pub const fn new(track_width: f64, wheelbase: f64) -> Self {
+        Self {
+            track_width,
+            wheelbase,
+        }
+    }
+
+    fn turn_radius(&self) -> f64 {
+        (self.track_width + self.wheelbase) / 2.0
+    }
+
+    /// Returns the wheel speeds needed to drive at the given chassis speeds.
+    pub fn to_wheel_speeds(&self, speeds: ChassisSpeeds) -> MecanumWheelSpeeds {
+        let turn = speeds.omega * self.turn_radius();
+        MecanumWheelSpeeds {
+            front_left: speeds.vx - speeds.vy - turn,
+            front_right: speeds.vx + speeds.vy + turn,
+            back_left: speeds.vx + speeds.vy - turn,
+            back_right: speeds.vx - speeds.vy + turn,
+        }
+    }
+
+    /// Returns the chassis speeds produced by the given wheel speeds.
+    pub fn to_chassis_speeds(&self, wheels: MecanumWheelSpeeds) -> ChassisSpeeds {
+        ChassisSpeeds {
+            vx: (wheels.front_left + wheels.front_right + wheels.back_left + wheels.back_right)
+                / 4.0,
+            vy: (-wheels.front_left + wheels.front_right + wheels.back_left - wheels.back_right)
+                / 4.0,
+            omega: (-wheels.front_left + wheels.front_right - wheels.back_left + wheels.back_right)
+                / (4.0 * self.turn_radius()),
+        }
+    }
+}
+
+/// The speed and direction of a swerve module.
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub struct SwerveModuleState {
+    /// Speed of the wheel in distance units per second.
+    pub speed: f64,
+    /// Direction of the wheel in radians counterclockwise from the robot's x axis.
+    pub angle: f64,
+}
+
+impl SwerveModuleState {
+    /// Returns an equivalent state that needs the module to turn by at most 90 degrees from `current_angle`,
+    /// reversing the wheel if necessary.
+    pub fn optimize(self, current_angle: f64) -> Self {
+        let delta = wrap_radians(self.angle - current_angle);
+        if delta.abs() > PI / 2.0 {
+            Self {
+                speed: -self.speed,
+                angle: wrap_radians(self.angle + PI),
+            }
+        } else {
+            self
+        }
+    }
+}
+
+/// Kinematics of a swerve drivetrain with `N` modules.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SwerveKinematics<const N: usize> {
+    /// Position of each module relative to the center of rotation of the robot.
+    pub modules: [Waypoint; N],
+}
+
+impl<const N: usize> SwerveKinematics<N> {
+    /// Creates a new swerve kinematics model from the position of each module.
+    pub const fn new(modules: [Waypoint; N]) -> Self {
+        Self { modules }
+    }
+
+    /// Returns the module states needed to drive at the given chassis speeds.
+    ///
+    /// When the robot is stopped, every module points forwards.
+    pub fn to_module_states(&self, speeds: ChassisSpeeds) -> [SwerveModuleState; N] {
+        self.modules.map(|module| {
+            let vx = speeds.vx - speeds.omega * module.y;
+            let vy = speeds.vy + speeds.omega * module.x;
+            SwerveModuleState {
+                speed: libm::hypot(vx, vy),
+                angle: libm::atan2(vy, vx),
+            }
+        })
+    }
+
+    /// Returns the chassis speeds that best match the given module states.
+    ///
+    /// Module states rarely agree perfectly, so this is a least-squares estimate.
+    pub fn to_chassis_speeds(&self, states: [SwerveModuleState; N]) -> ChassisSpeeds {
+        // Each module gives two equations: vx - ω·y = module vx and vy + ω·x = module vy.
+        // These are solved with the normal equations, which form a 3x3 system.
+        let n = N as f64;
+        let (mut sum_x, mut sum_y, mut sum_squared) = (0.0, 0.0, 0.0);
+        let (mut sum_vx, mut sum_vy, mut sum_moment) = (0.0, 0.0, 0.0);
+        for (module, state) in self.modules.iter().zip(states) {
+            let vx = state.speed * libm::cos(state.angle);
+            let vy = state.speed * libm::sin(state.angle);
+            sum_x += module.x;
+            sum_y += module.y;
+            sum_squared += module.x * module.x + module.y * module.y;
+            sum_vx += vx;
+            sum_vy += vy;
+            sum_moment += module.x * vy - module.y * vx;
+        }
+
+        let matrix = [
+            [n, 0.0, -sum_y],
+            [0.0, n, sum_x],
+            [-sum_y, sum_x, sum_squared],
+        ];
+        let rhs = [sum_vx, sum_vy, sum_moment];
+        let det = determinant(matrix);
+        if det.abs() < 1e-12 {
+            return ChassisSpeeds::default();
+        }
+
+        // Cramer's rule.
+        let solve = |column: usize| {
+            let mut replaced = matrix;
+            for (row, value) in replaced.iter_mut().zip(rhs) {
+                row[column] = value;
+            }
+            determinant(replaced) / det
+        };
+
+        ChassisSpeeds {
+            vx: solve(0),
+            vy: solve(1),
+            omega: solve(2),
+        }
+    }
+
+    /// Scales the module speeds down so that none of them exceed `max_speed`. See [`desaturate`].
+    pub fn desaturate(states: [SwerveModuleState; N], max_speed: f64) -> [SwerveModuleState; N] {
+        let mut speeds = states.map(|state| state.speed);
+        desaturate(&mut speeds, max_speed);
+        let mut states = states;
+        for (state, speed) in states.iter_mut().zip(speeds) {
+            state.speed = speed;
+        }
+        states
+    }
+}
+
+/// Returns the determinant of a 3x3 matrix.
+fn determinant(m: [[f64; 3]; 3]) -> f64 {
+    m[0][0] * (m[1][1] * m[2][2] - m[1][2] * m[2][1])
+        - m[0][1] * (m[1][0] * m[2][2] - m[1][2] * m[2][0])
+        + m[0][2] * (m[1][0] * m[2][1] - m[1][1] * m[2][0])
+}
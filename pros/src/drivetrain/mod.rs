@@ -0,0 +1,86 @@
+//! Drivetrains built out of smart motors.
+//!
+//! Drivetrains group the motors of a robot's base together and convert chassis-level
+//! commands (such as "drive forward while turning") into outputs for each individual motor.
+//!
+//! Currently supported drivetrains are:
+//! - [`Differential`](differential::DifferentialDrive) (tank)
+//! - [`Holonomic`](holonomic::HolonomicDrive) (X-drive and mecanum)
+//!
+//! The [`kinematics`] module contains hardware-independent models of these and other drivetrain layouts.
+
+use snafu::Snafu;
+
+use crate::{
+    motor::MotorError,
+    sensors::{
+        gps::{GpsError, GpsSensor},
+        imu::{InertialError, InertialSensor},
+        Gyro,
+    },
+};
+
+pub mod differential;
+pub mod holonomic;
+pub mod kinematics;
+
+/// A sensor that can report which direction the robot is facing on the field.
+pub enum HeadingSource {
+    /// Read the heading from an inertial sensor.
+    Imu(InertialSensor),
+    /// Read the heading from a GPS sensor.
+    Gps(GpsSensor),
+}
+
+impl HeadingSource {
+    /// Returns the heading of the robot in degrees.
+    ///
+    /// This value is bounded by [0, 360) degrees. Clockwise rotations are represented with positive degree values.
+    pub fn heading(&self) -> Result<f64, DrivetrainError> {
+        Ok(match self {
+            Self::Imu(imu) => imu.heading()?,
+            Self::Gps(gps) => gps.status()?.heading,
+        })
+    }
+}
+
+impl Gyro for HeadingSource {
+    type Error = DrivetrainError;
+
+    fn heading(&self) -> Result<f64, DrivetrainError> {
+        HeadingSource::heading(self)
+    }
+
+    fn rotation(&self) -> Result<f64, DrivetrainError> {
+        Ok(match self {
+            Self::Imu(imu) => imu.rotation()?,
+            Self::Gps(gps) => gps.rotation()?,
+        })
+    }
+}
+
+impl From<InertialSensor> for HeadingSource {
+    fn from(value: InertialSensor) -> Self {
+        Self::Imu(value)
+    }
+}
+
+impl From<GpsSensor> for HeadingSource {
+    fn from(value: GpsSensor) -> Self {
+        Self::Gps(value)
+    }
+}
+
+#[derive(Debug, Snafu)]
+pub enum DrivetrainError {
+    #[snafu(display("{source}"), context(false))]
+    Motor { source: MotorError },
+    #[snafu(display("{source}"), context(false))]
+    Inertial { source: InertialError },
+    #[snafu(display("{source}"), context(false))]
+    Gps { source: GpsError },
+    #[snafu(display(
+        "The maximum wheel speed must be positive and finite, but was {max_wheel_speed}."
+    ))]
+    InvalidMaxWheelSpeed { max_wheel_speed: f64 },
+}
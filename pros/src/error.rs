@@ -82,6 +82,8 @@ macro_rules! bail_on {
 pub(crate) use bail_on;
 use snafu::Snafu;
 
+use crate::devices::DeviceType;
+
 pub trait FromErrno {
     /// Consume the current `errno` and, if it contains a known error, returns Self.
     fn from_errno(num: i32) -> Option<Self>
@@ -98,6 +100,14 @@ pub enum PortError {
         "The port you specified couldn't be configured as what you specified."
     ))]
     PortCannotBeConfigured,
+    #[snafu(display(
+        "Wrong device plugged in: port {port} has a {found:?} device, but a {expected:?} device was expected."
+    ))]
+    WrongDevice {
+        port: u8,
+        expected: DeviceType,
+        found: DeviceType,
+    },
 }
 
 map_errno!(PortError {
@@ -0,0 +1,38 @@
+//! Feedforward controllers.
+//!
+//! Feedforward controllers predict the voltage needed to reach a velocity from a model of the mechanism,
+//! rather than reacting to error like a [`PidController`](crate::pid::PidController).
+//! The two are often combined, with feedforward doing most of the work and feedback correcting the rest.
+
+/// A feedforward model of a motor driving a mechanism with friction and inertia.
+///
+/// The output voltage is calculated as `ks * sign(velocity) + kv * velocity + ka * acceleration`.
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub struct MotorFeedforward {
+    /// Static gain. This is the voltage needed to overcome friction and start moving.
+    pub ks: f64,
+    /// Velocity gain. This is the voltage needed to hold one unit of velocity.
+    pub kv: f64,
+    /// Acceleration gain. This is the voltage needed to accelerate by one unit of velocity per second.
+    pub ka: f64,
+}
+
+impl MotorFeedforward {
+    /// Creates a new feedforward model.
+    pub const fn new(ks: f64, kv: f64, ka: f64) -> Self {
+        Self { ks, kv, ka }
+    }
+
+    /// Calculates the voltage needed to reach a velocity with the given acceleration.
+    pub fn calculate(&self, velocity: f64, acceleration: f64) -> f64 {
+        let friction = if velocity > 0.0 {
+            self.ks
+        } else if velocity < 0.0 {
+            -self.ks
+        } else {
+            0.0
+        };
+
+        friction + self.kv * velocity + self.ka * acceleration
+    }
+}
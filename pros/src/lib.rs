@@ -59,9 +59,16 @@ extern crate alloc;
 
 pub mod async_runtime;
 pub mod controller;
+pub mod devices;
+pub mod drivetrain;
 pub mod error;
+pub mod feedforward;
+pub mod motion;
 pub mod motor;
+pub mod odometry;
+pub mod peripherals;
 pub mod pid;
+pub mod pose_estimator;
 pub mod position;
 pub mod sensors;
 pub mod sync;
@@ -360,18 +367,43 @@ pub mod prelude {
     pub use crate::{async_trait, os_task_local, print, println};
     pub use alloc::boxed::Box;
 
+    pub use crate::adi::{
+        analog::*,
+        digital::*,
+        encoder::*,
+        gyro::*,
+        led::{animation::*, *},
+        potentiometer::*,
+        ultrasonic::*,
+        AdiError, AdiPort,
+    };
     pub use crate::async_runtime::*;
     pub use crate::controller::*;
+    pub use crate::devices::{monitor::*, *};
+    pub use crate::drivetrain::{
+        differential::*, holonomic::*, kinematics::*, DrivetrainError, HeadingSource,
+    };
     pub use crate::error::PortError;
+    pub use crate::feedforward::*;
     pub use crate::lcd::{buttons::Button, LcdError};
     pub use crate::link::*;
+    pub use crate::motion::{
+        import::*, primitives::*, pure_pursuit::*, ramsete::*, spline::*, trajectory::*,
+        MotionError, Waypoint,
+    };
     pub use crate::motor::*;
+    pub use crate::odometry::*;
+    pub use crate::peripherals::*;
     pub use crate::pid::*;
+    pub use crate::pose_estimator::*;
     pub use crate::position::*;
     pub use crate::sensors::distance::*;
     pub use crate::sensors::gps::*;
     pub use crate::sensors::imu::*;
+    pub use crate::sensors::optical::*;
     pub use crate::sensors::rotation::*;
-    pub use crate::sensors::vision::*;
+    pub use crate::sensors::stream::*;
+    pub use crate::sensors::vision::{tracker::*, *};
+    pub use crate::sensors::{Encoder, Gyro, RangeFinder};
     pub use crate::task::{sleep, spawn};
 }
@@ -10,7 +10,10 @@ use no_std_io::io;
 use pros_sys::{link::E_LINK_RECEIVER, link_receive, link_transmit, E_LINK_TRANSMITTER};
 use snafu::Snafu;
 
-use crate::error::{bail_errno, bail_on, map_errno, FromErrno, PortError};
+use crate::{
+    devices::{validate_port, DeviceType, SmartDevice, SmartPort},
+    error::{bail_errno, bail_on, map_errno, FromErrno, PortError},
+};
 
 /// Types that implement Link can be used to send data to another robot over VEXLink.
 pub trait Link {
@@ -23,7 +26,7 @@ pub trait Link {
         unsafe { pros_sys::link_connected(self.port()) }
     }
     /// Create a new link ready to send or recieve data.
-    fn new(port: u8, id: String, vexlink_override: bool) -> Result<Self, LinkError>
+    fn new(port: SmartPort, id: String, vexlink_override: bool) -> Result<Self, LinkError>
     where
         Self: Sized;
 }
@@ -78,7 +81,9 @@ impl Link for RxLink {
     fn port(&self) -> u8 {
         self.port
     }
-    fn new(port: u8, id: String, vexlink_override: bool) -> Result<Self, LinkError> {
+    fn new(port: SmartPort, id: String, vexlink_override: bool) -> Result<Self, LinkError> {
+        let port = port.index();
+        validate_port(port, DeviceType::Radio)?;
         let id = CString::new(id).unwrap();
         unsafe {
             bail_on!(
@@ -94,6 +99,16 @@ impl Link for RxLink {
     }
 }
 
+impl SmartDevice for RxLink {
+    fn port_index(&self) -> u8 {
+        self.port
+    }
+
+    fn device_type(&self) -> DeviceType {
+        DeviceType::Radio
+    }
+}
+
 impl io::Read for RxLink {
     fn read(&mut self, dst: &mut [u8]) -> io::Result<usize> {
         let bytes_read = self
@@ -157,7 +172,9 @@ impl Link for TxLink {
     fn port(&self) -> u8 {
         self.port
     }
-    fn new(port: u8, id: String, vexlink_override: bool) -> Result<Self, LinkError> {
+    fn new(port: SmartPort, id: String, vexlink_override: bool) -> Result<Self, LinkError> {
+        let port = port.index();
+        validate_port(port, DeviceType::Radio)?;
         let id = CString::new(id).unwrap();
         unsafe {
             bail_on!(
@@ -173,6 +190,16 @@ impl Link for TxLink {
     }
 }
 
+impl SmartDevice for TxLink {
+    fn port_index(&self) -> u8 {
+        self.port
+    }
+
+    fn device_type(&self) -> DeviceType {
+        DeviceType::Radio
+    }
+}
+
 #[derive(Debug, Snafu)]
 pub enum LinkError {
     #[snafu(display("No link is connected through the radio."))]
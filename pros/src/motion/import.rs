@@ -0,0 +1,256 @@
+//! Loading paths and trajectories made with external path planners.
+//!
+//! Paths and trajectories can be read from the SD card with [`load_path`] and [`load_trajectory`],
+//! so routes can be changed without recompiling the program, or parsed from text that is already in
+//! memory (such as a file embedded with [`include_str!`]) with [`parse_path`] and [`parse_trajectory`].
+//!
+//! Two formats are supported, chosen by the file extension when loading from the SD card:
+//! - CSV paths have one waypoint per line, starting with its x and y coordinates.
+//!   Extra columns (such as the speed column written by path.jerryio for LemLib) are ignored,
+//!   as is everything after an `endData` line.
+//!   CSV trajectories have one sample per line with the columns
+//!   `time, x, y, heading, velocity, acceleration, curvature`, where the last two columns are optional.
+//!   A header line and lines starting with `#` are skipped.
+//! - JSON paths are either an array of waypoints or an object with a `waypoints` array.
+//!   Waypoints can be `[x, y]` arrays, `{"x": .., "y": ..}` objects, or PathPlanner waypoints with an
+//!   `anchor` (or `anchorPoint`) point. JSON trajectories use the WPILib trajectory format.
+//!
+//! Headings are in radians counterclockwise from the x axis, and distances are in whatever units the
+//! path planner was set up with.
+//!
+//! Example of loading a skills route from the SD card:
+//! ```rust
+//! let path = load_path("paths/skills.csv")?;
+//! PurePursuit::new(12.0, 40.0, 80.0).follow(&drive, &odometry, &path).await?;
+//! ```
+
+use alloc::{string::String, vec::Vec};
+
+use snafu::Snafu;
+
+use self::json::Value;
+
+use super::{
+    trajectory::{Trajectory, TrajectorySample},
+    Waypoint,
+};
+use crate::{
+    odometry::Pose,
+    usd::{self, UsdError},
+};
+
+mod json;
+
+/// The format of a path or trajectory file.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PathFormat {
+    /// Comma-separated values.
+    Csv,
+    /// JSON.
+    Json,
+}
+
+impl PathFormat {
+    /// Guesses the format of a file from its extension.
+    pub fn from_path(path: &str) -> Option<Self> {
+        let (_, extension) = path.rsplit_once('.')?;
+        if extension.eq_ignore_ascii_case("csv") || extension.eq_ignore_ascii_case("txt") {
+            Some(Self::Csv)
+        } else if extension.eq_ignore_ascii_case("json") || extension.eq_ignore_ascii_case("path") {
+            Some(Self::Json)
+        } else {
+            None
+        }
+    }
+}
+
+/// Loads a path from a file on the SD card. The format is chosen from the file extension.
+pub fn load_path(path: &str) -> Result<Vec<Waypoint>, ImportError> {
+    let format = PathFormat::from_path(path).ok_or_else(|| ImportError::UnknownFormat {
+        path: String::from(path),
+    })?;
+    parse_path(&usd::read_to_string(path)?, format)
+}
+
+/// Loads a trajectory from a file on the SD card. The format is chosen from the file extension.
+pub fn load_trajectory(path: &str) -> Result<Trajectory, ImportError> {
+    let format = PathFormat::from_path(path).ok_or_else(|| ImportError::UnknownFormat {
+        path: String::from(path),
+    })?;
+    parse_trajectory(&usd::read_to_string(path)?, format)
+}
+
+/// Parses a path from text.
+pub fn parse_path(text: &str, format: PathFormat) -> Result<Vec<Waypoint>, ImportError> {
+    match format {
+        PathFormat::Csv => csv_rows(text, 2)
+            .map(|row| row.map(|values| Waypoint::new(values[0], values[1])))
+            .collect(),
+        PathFormat::Json => {
+            let json = parse_json(text)?;
+            let waypoints = json.get("waypoints").unwrap_or(&json).as_array().ok_or(
+                ImportError::InvalidStructure {
+                    message: "expected an array of waypoints",
+                },
+            )?;
+
+            waypoints
+                .iter()
+                .map(|waypoint| {
+                    let point = waypoint
+                        .get("anchor")
+                        .or_else(|| waypoint.get("anchorPoint"))
+                        .unwrap_or(waypoint);
+                    json_point(point).ok_or(ImportError::InvalidStructure {
+                        message: "expected each waypoint to have an x and y coordinate",
+                    })
+                })
+                .collect()
+        }
+    }
+}
+
+/// Parses a trajectory from text. Samples must be sorted by time.
+pub fn parse_trajectory(text: &str, format: PathFormat) -> Result<Trajectory, ImportError> {
+    let samples = match format {
+        PathFormat::Csv => csv_rows(text, 5)
+            .map(|row| {
+                row.map(|values| {
+                    TrajectorySample::new(
+                        values[0],
+                        Pose::new(values[1], values[2], values[3]),
+                        values[4],
+                        values.get(5).copied().unwrap_or(0.0),
+                        values.get(6).copied().unwrap_or(0.0),
+                    )
+                })
+            })
+            .collect::<Result<Vec<_>, _>>()?,
+        PathFormat::Json => parse_json(text)?
+            .as_array()
+            .ok_or(ImportError::InvalidStructure {
+                message: "expected an array of trajectory samples",
+            })?
+            .iter()
+            .map(|sample| {
+                json_sample(sample).ok_or(ImportError::InvalidStructure {
+                    message: "expected each sample to have a time, velocity, acceleration, pose and curvature",
+                })
+            })
+            .collect::<Result<Vec<_>, _>>()?,
+    };
+
+    Ok(Trajectory::new(samples))
+}
+
+/// Returns the numeric values on every data line of a CSV file.
+///
+/// Each row has at least `min_columns` values. Stops at an `endData` line.
+fn csv_rows(
+    text: &str,
+    min_columns: usize,
+) -> impl Iterator<Item = Result<Vec<f64>, ImportError>> + '_ {
+    text.lines()
+        .enumerate()
+        .map(|(index, line)| (index + 1, line.trim()))
+        .take_while(|(_, line)| *line != "endData")
+        .filter(|(_, line)| !line.is_empty() && !line.starts_with('#'))
+        .enumerate()
+        .filter_map(move |(row, (line_number, line))| {
+            let fields = line.split(',').map(str::trim);
+            // The first row is a header if it doesn't start with a number.
+            if row == 0
+                && line
+                    .split(',')
+                    .next()
+                    .is_some_and(|field| field.trim().parse::<f64>().is_err())
+            {
+                return None;
+            }
+
+            let values = fields
+                .map(|field| {
+                    field
+                        .parse::<f64>()
+                        .map_err(|_| ImportError::InvalidNumber {
+                            line: line_number,
+                            value: String::from(field),
+                        })
+                })
+                .collect::<Result<Vec<_>, _>>();
+
+            Some(values.and_then(|values| {
+                if values.len() < min_columns {
+                    Err(ImportError::MissingColumns {
+                        line: line_number,
+                        expected: min_columns,
+                        found: values.len(),
+                    })
+                } else {
+                    Ok(values)
+                }
+            }))
+        })
+}
+
+fn parse_json(text: &str) -> Result<Value, ImportError> {
+    json::parse(text).map_err(|err| ImportError::InvalidJson {
+        line: err.line,
+        column: err.column,
+        message: err.message,
+    })
+}
+
+/// Reads a point from an `[x, y]` array or an object with `x` and `y` fields.
+fn json_point(value: &Value) -> Option<Waypoint> {
+    match value {
+        Value::Array(coordinates) => Some(Waypoint::new(
+            coordinates.first()?.as_f64()?,
+            coordinates.get(1)?.as_f64()?,
+        )),
+        Value::Object(_) => Some(Waypoint::new(
+            value.get("x")?.as_f64()?,
+            value.get("y")?.as_f64()?,
+        )),
+        _ => None,
+    }
+}
+
+/// Reads a sample in the WPILib trajectory format.
+fn json_sample(value: &Value) -> Option<TrajectorySample> {
+    let pose = value.get("pose")?;
+    let translation = json_point(pose.get("translation")?)?;
+    let heading = pose.get("rotation")?.get("radians")?.as_f64()?;
+
+    Some(TrajectorySample::new(
+        value.get("time")?.as_f64()?,
+        Pose::new(translation.x, translation.y, heading),
+        value.get("velocity")?.as_f64()?,
+        value.get("acceleration")?.as_f64()?,
+        value.get("curvature")?.as_f64()?,
+    ))
+}
+
+#[derive(Debug, Snafu)]
+pub enum ImportError {
+    #[snafu(display("Can't tell the format of `{path}`. Use a .csv or .json file."))]
+    UnknownFormat { path: String },
+    #[snafu(display("Line {line}: `{value}` is not a number."))]
+    InvalidNumber { line: usize, value: String },
+    #[snafu(display("Line {line}: expected at least {expected} columns, found {found}."))]
+    MissingColumns {
+        line: usize,
+        expected: usize,
+        found: usize,
+    },
+    #[snafu(display("Line {line}, column {column}: {message}."))]
+    InvalidJson {
+        line: usize,
+        column: usize,
+        message: &'static str,
+    },
+    #[snafu(display("Invalid file: {message}."))]
+    InvalidStructure { message: &'static str },
+    #[snafu(display("{source}"), context(false))]
+    Usd { source: UsdError },
+}
@@ -0,0 +1,113 @@
+//! Autonomous motion control.
+//!
+//! Motion controllers drive a [`DifferentialDrive`](crate::drivetrain::differential::DifferentialDrive)
+//! using the robot's [`Pose`](crate::odometry::Pose), which is read from a [`PoseSource`](crate::odometry::PoseSource).
+//! Every controller is an async function that completes when the motion is finished.
+//!
+//! Motions are automatically cancelled with [`MotionError::Cancelled`] if the
+//! [competition mode](crate::competition::CompetitionMode) changes while they are running,
+//! so an autonomous routine can never keep driving into driver control.
+//! Dropping the future also cancels the motion, although the drivetrain will keep its last output.
+
+use core::{f64::consts::TAU, future::Future, time::Duration};
+
+use snafu::Snafu;
+
+use crate::{
+    adi::AdiError,
+    competition::{self, CompetitionMode},
+    drivetrain::{differential::DifferentialDrive, DrivetrainError},
+    odometry::Pose,
+    sensors::{gps::GpsError, imu::InertialError},
+};
+
+pub mod import;
+pub mod primitives;
+pub mod pure_pursuit;
+pub mod ramsete;
+pub mod spline;
+pub mod trajectory;
+
+/// How often motion controllers update their outputs.
+pub const MOTION_UPDATE_INTERVAL: Duration = Duration::from_millis(10);
+
+/// A point on a path.
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub struct Waypoint {
+    pub x: f64,
+    pub y: f64,
+}
+
+impl Waypoint {
+    /// Creates a new waypoint.
+    pub const fn new(x: f64, y: f64) -> Self {
+        Self { x, y }
+    }
+
+    /// Returns the straight line distance to another waypoint.
+    pub fn distance(&self, other: Waypoint) -> f64 {
+        libm::hypot(other.x - self.x, other.y - self.y)
+    }
+}
+
+impl From<Pose> for Waypoint {
+    fn from(pose: Pose) -> Self {
+        Self::new(pose.x, pose.y)
+    }
+}
+
+/// Wraps an angle in radians to the range [-π, π].
+pub(crate) fn wrap_radians(angle: f64) -> f64 {
+    angle - TAU * libm::round(angle / TAU)
+}
+
+/// Runs a motion and then brakes the drivetrain, even if the motion failed.
+///
+/// An error from the motion (such as it being cancelled) is returned instead of one from braking.
+pub(crate) async fn run_then_brake(
+    drive: &DifferentialDrive,
+    motion: impl Future<Output = Result<(), MotionError>>,
+) -> Result<(), MotionError> {
+    let result = motion.await;
+    let brake = drive.brake();
+    result?;
+    Ok(brake?)
+}
+
+/// Keeps track of the competition mode a motion was started in.
+pub(crate) struct ModeGuard {
+    mode: CompetitionMode,
+}
+
+impl ModeGuard {
+    pub(crate) fn new() -> Self {
+        Self {
+            mode: competition::get_mode(),
+        }
+    }
+
+    /// Returns an error if the competition mode has changed since the guard was created.
+    pub(crate) fn check(&self) -> Result<(), MotionError> {
+        if competition::get_mode() == self.mode {
+            Ok(())
+        } else {
+            Err(MotionError::Cancelled)
+        }
+    }
+}
+
+#[derive(Debug, Snafu)]
+pub enum MotionError {
+    #[snafu(display("The competition mode changed before the motion finished."))]
+    Cancelled,
+    #[snafu(display("The motion did not finish before its timeout."))]
+    TimedOut,
+    #[snafu(display("{source}"), context(false))]
+    Drivetrain { source: DrivetrainError },
+    #[snafu(display("{source}"), context(false))]
+    Inertial { source: InertialError },
+    #[snafu(display("{source}"), context(false))]
+    Gps { source: GpsError },
+    #[snafu(display("{source}"), context(false))]
+    Adi { source: AdiError },
+}
@@ -0,0 +1,321 @@
+//! Basic motions for differential drivetrains.
+//!
+//! These are the building blocks of most autonomous routines:
+//! - [`DifferentialDrive::turn_to_heading`] turns in place to face a heading reported by a [`Gyro`], such as an inertial sensor.
+//! - [`DifferentialDrive::turn_to_point`] turns in place to face a point on the field.
+//! - [`DifferentialDrive::drive_distance`] drives straight forwards or backwards.
+//! - [`DifferentialDrive::move_to_pose`] drives to a point and arrives facing a given heading.
+//!
+//! Every motion is driven by [`PidController`]s and finishes once its [`ExitConditions`] are met.
+//! PID outputs are used directly as drivetrain outputs from -1 to 1, so the gains should be tuned accordingly.
+//!
+//! Like [`Pose`] headings, every angle taken by these motions is in radians and counterclockwise is positive,
+//! including headings read from a [`Gyro`].
+//!
+//! Example of a simple autonomous routine:
+//! ```rust
+//! let mut linear = PidController::new(0.1, 0.0, 0.01);
+//! let mut angular = PidController::new(1.0, 0.0, 0.1);
+//!
+//! drive
+//!     .drive_distance(&imu, 24.0, &mut linear, &mut angular, ExitConditions::new(0.5, Duration::from_millis(100)))
+//!     .await?;
+//! // Turn 90 degrees to the right.
+//! drive
+//!     .turn_to_heading(&imu, -FRAC_PI_2, &mut angular, ExitConditions::new(0.02, Duration::from_millis(100)))
+//!     .await?;
+//! ```
+
+use core::time::Duration;
+
+use super::{run_then_brake, wrap_radians, ModeGuard, MotionError, MOTION_UPDATE_INTERVAL};
+use crate::{
+    drivetrain::differential::DifferentialDrive,
+    odometry::{Pose, PoseSource},
+    pid::PidController,
+    sensors::Gyro,
+    task::sleep,
+};
+
+/// Determines when a motion is finished.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ExitConditions {
+    /// How close the robot needs to be to its target, in the units of the motion's error.
+    pub tolerance: f64,
+    /// How long the robot needs to stay within the tolerance before the motion finishes.
+    pub settle_time: Duration,
+    /// How long the motion can run before it fails with [`MotionError::TimedOut`].
+    pub timeout: Option<Duration>,
+}
+
+impl ExitConditions {
+    /// Creates new exit conditions without a timeout.
+    pub const fn new(tolerance: f64, settle_time: Duration) -> Self {
+        Self {
+            tolerance,
+            settle_time,
+            timeout: None,
+        }
+    }
+
+    /// Sets the timeout of the motion.
+    pub const fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = Some(timeout);
+        self
+    }
+}
+
+/// Settings for [`DifferentialDrive::move_to_pose`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct MoveToPoseSettings {
+    /// How far the carrot starts from the target, from 0 to 1.
+    ///
+    /// 0 drives straight to the target, and higher values make the robot curve in more.
+    pub lead: f64,
+    /// How close the robot's heading needs to be to the target heading for the motion to finish, in radians.
+    pub heading_tolerance: f64,
+    /// When the motion finishes. The tolerance is in distance units.
+    pub exit: ExitConditions,
+}
+
+impl MoveToPoseSettings {
+    /// Creates new settings for driving to a pose.
+    pub const fn new(lead: f64, heading_tolerance: f64, exit: ExitConditions) -> Self {
+        Self {
+            lead,
+            heading_tolerance,
+            exit,
+        }
+    }
+}
+
+/// Keeps track of how long a motion has been running and settled for.
+struct ExitTracker {
+    conditions: ExitConditions,
+    guard: ModeGuard,
+    start: u32,
+    settled_since: Option<u32>,
+}
+
+impl ExitTracker {
+    fn new(conditions: ExitConditions) -> Self {
+        Self {
+            conditions,
+            guard: ModeGuard::new(),
+            start: unsafe { pros_sys::millis() },
+            settled_since: None,
+        }
+    }
+
+    /// Returns true if the motion is finished, or an error if it was cancelled or timed out.
+    fn update(&mut self, error: f64) -> Result<bool, MotionError> {
+        self.update_settled(error.abs() <= self.conditions.tolerance)
+    }
+
+    /// Like [`update`](Self::update), but with the check of whether the robot is within tolerance already done.
+    fn update_settled(&mut self, within_tolerance: bool) -> Result<bool, MotionError> {
+        self.guard.check()?;
+
+        let now = unsafe { pros_sys::millis() };
+        if let Some(timeout) = self.conditions.timeout {
+            if now - self.start > timeout.as_millis() as u32 {
+                return Err(MotionError::TimedOut);
+            }
+        }
+
+        if !within_tolerance {
+            self.settled_since = None;
+            return Ok(false);
+        }
+
+        let settled_since = *self.settled_since.get_or_insert(now);
+        Ok(now - settled_since >= self.conditions.settle_time.as_millis() as u32)
+    }
+}
+
+/// Returns the heading reported by a gyro in radians counterclockwise.
+fn gyro_heading<G>(gyro: &G) -> Result<f64, MotionError>
+where
+    G: Gyro,
+    MotionError: From<G::Error>,
+{
+    Ok(-gyro.heading()?.to_radians())
+}
+
+/// Runs a PID controller on an error that was already computed (such as a wrapped angle).
+fn pid_on_error(pid: &mut PidController, error: f64) -> f32 {
+    // Using the negated error as the measurement keeps the derivative acting on the robot's movement.
+    pid.update(0.0, -error as f32).clamp(-1.0, 1.0)
+}
+
+impl DifferentialDrive {
+    /// Turns in place until the gyro reports the given heading.
+    ///
+    /// `heading` is in radians counterclockwise, so the gyro's clockwise degrees are converted before comparing.
+    /// The robot always takes the shortest way around. The tolerance of `exit` is in radians.
+    pub async fn turn_to_heading<G>(
+        &self,
+        gyro: &G,
+        heading: f64,
+        pid: &mut PidController,
+        exit: ExitConditions,
+    ) -> Result<(), MotionError>
+    where
+        G: Gyro,
+        MotionError: From<G::Error>,
+    {
+        run_then_brake(self, async {
+            let mut tracker = ExitTracker::new(exit);
+            pid.reset();
+
+            loop {
+                let error = wrap_radians(heading - gyro_heading(gyro)?);
+                if tracker.update(error)? {
+                    return Ok(());
+                }
+
+                // Positive outputs turn the drivetrain clockwise.
+                self.arcade(0.0, -pid_on_error(pid, error))?;
+                sleep(MOTION_UPDATE_INTERVAL).await;
+            }
+        })
+        .await
+    }
+
+    /// Turns in place until the front of the robot faces the given point.
+    ///
+    /// The point is in the coordinate system of `pose_source`. The tolerance of `exit` is in radians.
+    pub async fn turn_to_point(
+        &self,
+        pose_source: &impl PoseSource,
+        x: f64,
+        y: f64,
+        pid: &mut PidController,
+        exit: ExitConditions,
+    ) -> Result<(), MotionError> {
+        run_then_brake(self, async {
+            let mut tracker = ExitTracker::new(exit);
+            pid.reset();
+
+            loop {
+                let pose = pose_source.pose();
+                let target = libm::atan2(y - pose.y, x - pose.x);
+                // Pose headings are counterclockwise, but the drivetrain turns clockwise for positive outputs.
+                let error = wrap_radians(target - pose.heading);
+                if tracker.update(error)? {
+                    return Ok(());
+                }
+
+                self.arcade(0.0, -pid_on_error(pid, error))?;
+                sleep(MOTION_UPDATE_INTERVAL).await;
+            }
+        })
+        .await
+    }
+
+    /// Drives forwards (or backwards, for negative distances) in a straight line.
+    ///
+    /// The distance is measured with the drivetrain's motor encoders, while `angular_pid`
+    /// holds the heading the gyro reported when the motion started.
+    /// The tolerance of `exit` is in the drivetrain's distance units.
+    pub async fn drive_distance<G>(
+        &self,
+        gyro: &G,
+        distance: f64,
+        linear_pid: &mut PidController,
+        angular_pid: &mut PidController,
+        exit: ExitConditions,
+    ) -> Result<(), MotionError>
+    where
+        G: Gyro,
+        MotionError: From<G::Error>,
+    {
+        run_then_brake(self, async {
+            let mut tracker = ExitTracker::new(exit);
+            linear_pid.reset();
+            angular_pid.reset();
+
+            let start = self.distance()?;
+            let heading = gyro_heading(gyro)?;
+
+            loop {
+                let travelled = self.distance()? - start;
+                let linear_error = distance - travelled;
+                if tracker.update(linear_error)? {
+                    return Ok(());
+                }
+
+                let angular_error = wrap_radians(heading - gyro_heading(gyro)?);
+                self.arcade(
+                    pid_on_error(linear_pid, linear_error),
+                    -pid_on_error(angular_pid, angular_error),
+                )?;
+                sleep(MOTION_UPDATE_INTERVAL).await;
+            }
+        })
+        .await
+    }
+
+    /// Drives to a pose using a boomerang controller.
+    ///
+    /// Instead of driving straight at the target, the robot chases a "carrot" point that starts
+    /// behind the target (opposite to its heading) and slides onto it as the robot gets closer,
+    /// so the robot curves in and arrives facing `target.heading`.
+    /// See [`MoveToPoseSettings`] for how the carrot is placed and when the motion finishes.
+    ///
+    /// The pose is in the coordinate system of `pose_source`.
+    pub async fn move_to_pose(
+        &self,
+        pose_source: &impl PoseSource,
+        target: Pose,
+        linear_pid: &mut PidController,
+        angular_pid: &mut PidController,
+        settings: MoveToPoseSettings,
+    ) -> Result<(), MotionError> {
+        let MoveToPoseSettings {
+            lead,
+            heading_tolerance,
+            exit,
+        } = settings;
+
+        run_then_brake(self, async {
+            let mut tracker = ExitTracker::new(exit);
+            linear_pid.reset();
+            angular_pid.reset();
+
+            let (target_sin, target_cos) = (libm::sin(target.heading), libm::cos(target.heading));
+
+            loop {
+                let pose = pose_source.pose();
+                let distance = libm::hypot(target.x - pose.x, target.y - pose.y);
+                let heading_error = wrap_radians(target.heading - pose.heading);
+                if tracker.update_settled(
+                    distance <= exit.tolerance && heading_error.abs() <= heading_tolerance,
+                )? {
+                    return Ok(());
+                }
+
+                let carrot_x = target.x - lead * distance * target_cos;
+                let carrot_y = target.y - lead * distance * target_sin;
+                let (dx, dy) = (carrot_x - pose.x, carrot_y - pose.y);
+
+                // Once the robot is on top of the target, only correct its final heading.
+                let angular_error = if distance < exit.tolerance * 2.0 {
+                    heading_error
+                } else {
+                    wrap_radians(libm::atan2(dy, dx) - pose.heading)
+                };
+
+                // Slow down when facing away from the carrot so that the robot turns towards it first.
+                let linear_error = libm::hypot(dx, dy) * libm::cos(angular_error).max(0.0);
+
+                self.arcade(
+                    pid_on_error(linear_pid, linear_error),
+                    -pid_on_error(angular_pid, angular_error),
+                )?;
+                sleep(MOTION_UPDATE_INTERVAL).await;
+            }
+        })
+        .await
+    }
+}
@@ -0,0 +1,300 @@
+//! Pure pursuit path following.
+//!
+//! Pure pursuit follows a path by repeatedly driving along the arc that connects the robot to a
+//! point on the path a fixed distance ahead of it (the lookahead point).
+//! Smaller lookahead distances follow the path more closely, while larger ones produce smoother motion.
+//!
+//! Velocities are limited by the curvature of the path so that neither side of the drivetrain has to exceed
+//! the maximum velocity, and the robot decelerates smoothly into the end of the path.
+//!
+//! Example of following a path:
+//! ```rust
+//! let path = [
+//!     Waypoint::new(0.0, 0.0),
+//!     Waypoint::new(24.0, 0.0),
+//!     Waypoint::new(48.0, 24.0),
+//! ];
+//! let controller = PurePursuit::new(12.0, 40.0, 80.0);
+//! controller.follow(&drive, &odometry, &path).await?;
+//! ```
+
+use core::time::Duration;
+
+use alloc::vec::Vec;
+
+use super::{run_then_brake, ModeGuard, MotionError, Waypoint, MOTION_UPDATE_INTERVAL};
+use crate::{
+    drivetrain::differential::DifferentialDrive,
+    odometry::{Pose, PoseSource},
+    task::sleep,
+};
+
+/// A pure pursuit path follower for differential drivetrains.
+///
+/// All distances and velocities are in the units of the [`DifferentialDrive`] being controlled.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PurePursuit {
+    /// Distance from the robot to the point on the path it drives towards.
+    pub lookahead: f64,
+    /// Maximum velocity of either side of the drivetrain.
+    pub max_velocity: f64,
+    /// Maximum acceleration of the robot. This also controls how quickly the robot slows down at the end of the path.
+    pub max_acceleration: f64,
+    /// Optional limit on the angular velocity of the robot in radians per second.
+    ///
+    /// Lowering this slows the robot down on sharp curves.
+    pub max_angular_velocity: Option<f64>,
+    /// How close the robot needs to be to the end of the path for the motion to finish.
+    pub settle_tolerance: f64,
+    /// How long the robot can follow the path before giving up.
+    pub timeout: Option<Duration>,
+}
+
+impl PurePursuit {
+    /// Creates a new pure pursuit controller.
+    ///
+    /// The controller finishes once the robot is within 1 unit of the end of the path,
+    /// and has no angular velocity limit or timeout.
+    pub fn new(lookahead: f64, max_velocity: f64, max_acceleration: f64) -> Self {
+        Self {
+            lookahead,
+            max_velocity,
+            max_acceleration,
+            max_angular_velocity: None,
+            settle_tolerance: 1.0,
+            timeout: None,
+        }
+    }
+
+    /// Drives along the given path until the robot reaches its end.
+    ///
+    /// The robot always drives forwards along the path. The drivetrain is braked when the motion finishes,
+    /// times out, or is cancelled because the competition mode changed.
+    pub async fn follow(
+        &self,
+        drive: &DifferentialDrive,
+        pose_source: &impl PoseSource,
+        path: &[Waypoint],
+    ) -> Result<(), MotionError> {
+        run_then_brake(drive, self.follow_inner(drive, pose_source, path)).await
+    }
+
+    async fn follow_inner(
+        &self,
+        drive: &DifferentialDrive,
+        pose_source: &impl PoseSource,
+        path: &[Waypoint],
+    ) -> Result<(), MotionError> {
+        let guard = ModeGuard::new();
+        let start = unsafe { pros_sys::millis() };
+        let Some(&end) = path.last() else {
+            return Ok(());
+        };
+
+        let profile = self.profile(path, drive.track_width());
+        let total_distance = profile.last().map_or(0.0, |sample| sample.distance);
+        let dt = MOTION_UPDATE_INTERVAL.as_secs_f64();
+
+        let mut closest = 0;
+        let mut lookahead_index = 0.0;
+        let mut lookahead_point = path[0];
+        let mut velocity = 0.0;
+
+        loop {
+            guard.check()?;
+            if let Some(timeout) = self.timeout {
+                if unsafe { pros_sys::millis() } - start > timeout.as_millis() as u32 {
+                    return Err(MotionError::TimedOut);
+                }
+            }
+
+            let pose = pose_source.pose();
+            let position = Waypoint::from(pose);
+
+            // The closest point can only move forward, and only waypoints up to one lookahead distance
+            // further along the path are searched, so that the robot never backtracks or skips ahead
+            // when the path crosses over itself.
+            let window_end = (closest + 1..path.len())
+                .find(|&i| profile[i].distance - profile[closest].distance > self.lookahead)
+                .map_or(path.len(), |i| i + 1);
+            closest = (closest..window_end)
+                .min_by(|&a, &b| {
+                    position
+                        .distance(path[a])
+                        .total_cmp(&position.distance(path[b]))
+                })
+                .unwrap_or(closest);
+
+            let distance_to_end = position.distance(end);
+            if distance_to_end < self.settle_tolerance
+                || (closest == path.len() - 1 && passed_end(path, position))
+            {
+                return Ok(());
+            }
+
+            if distance_to_end < self.lookahead {
+                lookahead_point = end;
+            } else if let Some((index, point)) =
+                self.find_lookahead(path, position, lookahead_index)
+            {
+                lookahead_index = index;
+                lookahead_point = point;
+            }
+
+            // Slow down so that the robot can stop by the end of the path.
+            let remaining = (total_distance
+                - profile[closest].distance
+                - progress_past(path, closest, position))
+            .max(0.0);
+            let target = profile[closest]
+                .velocity
+                .min(libm::sqrt(2.0 * self.max_acceleration * remaining));
+            velocity = target.min(velocity + self.max_acceleration * dt);
+
+            let half_turn = curvature_to(pose, lookahead_point) * drive.track_width() / 2.0;
+            drive.set_velocities(velocity * (1.0 - half_turn), velocity * (1.0 + half_turn))?;
+
+            sleep(MOTION_UPDATE_INTERVAL).await;
+        }
+    }
+
+    /// Computes the distance along the path and the maximum velocity at every waypoint.
+    fn profile(&self, path: &[Waypoint], track_width: f64) -> Vec<ProfileSample> {
+        let mut profile = Vec::with_capacity(path.len());
+        let mut distance = 0.0;
+
+        for (i, &point) in path.iter().enumerate() {
+            if i > 0 {
+                distance += path[i - 1].distance(point);
+            }
+
+            let curvature = if i > 0 && i + 1 < path.len() {
+                three_point_curvature(path[i - 1], point, path[i + 1])
+            } else {
+                0.0
+            };
+
+            // The outer side of the drivetrain moves faster than the center on a curve,
+            // so the center has to slow down for it to stay under the maximum velocity.
+            let mut velocity = self.max_velocity / (1.0 + curvature * track_width / 2.0);
+            if let Some(max_angular_velocity) = self.max_angular_velocity {
+                if curvature > 0.0 {
+                    velocity = velocity.min(max_angular_velocity / curvature);
+                }
+            }
+
+            profile.push(ProfileSample { distance, velocity });
+        }
+
+        // Make sure the robot can slow down in time for sharp curves.
+        for i in (0..profile.len().saturating_sub(1)).rev() {
+            let gap = profile[i + 1].distance - profile[i].distance;
+            let next = profile[i + 1].velocity;
+            let reachable = libm::sqrt(next * next + 2.0 * self.max_acceleration * gap);
+            profile[i].velocity = profile[i].velocity.min(reachable);
+        }
+
+        profile
+    }
+
+    /// Finds where a circle of radius `lookahead` around the robot intersects the path.
+    ///
+    /// Returns the fractional index of the intersection along with the intersection itself.
+    /// Intersections before `last_index` are ignored so that the lookahead point never moves backwards.
+    fn find_lookahead(
+        &self,
+        path: &[Waypoint],
+        position: Waypoint,
+        last_index: f64,
+    ) -> Option<(f64, Waypoint)> {
+        for i in (last_index as usize)..path.len().saturating_sub(1) {
+            let (start, end) = (path[i], path[i + 1]);
+            let (dx, dy) = (end.x - start.x, end.y - start.y);
+            let (fx, fy) = (start.x - position.x, start.y - position.y);
+
+            let a = dx * dx + dy * dy;
+            let b = 2.0 * (fx * dx + fy * dy);
+            let c = fx * fx + fy * fy - self.lookahead * self.lookahead;
+            let discriminant = b * b - 4.0 * a * c;
+            if a == 0.0 || discriminant < 0.0 {
+                continue;
+            }
+
+            let root = libm::sqrt(discriminant);
+            // Prefer the intersection further along the segment.
+            for t in [(-b + root) / (2.0 * a), (-b - root) / (2.0 * a)] {
+                let index = i as f64 + t;
+                if (0.0..=1.0).contains(&t) && index >= last_index {
+                    return Some((index, Waypoint::new(start.x + t * dx, start.y + t * dy)));
+                }
+            }
+        }
+
+        None
+    }
+}
+
+struct ProfileSample {
+    distance: f64,
+    velocity: f64,
+}
+
+/// Returns the signed curvature of the arc from the robot to a point (positive is counterclockwise).
+fn curvature_to(pose: Pose, point: Waypoint) -> f64 {
+    let (dx, dy) = (point.x - pose.x, point.y - pose.y);
+    let distance_squared = dx * dx + dy * dy;
+    if distance_squared == 0.0 {
+        return 0.0;
+    }
+
+    // Offset of the point to the left of the robot.
+    let lateral = -libm::sin(pose.heading) * dx + libm::cos(pose.heading) * dy;
+    2.0 * lateral / distance_squared
+}
+
+/// Returns the unsigned curvature of the circle passing through three points.
+fn three_point_curvature(a: Waypoint, b: Waypoint, c: Waypoint) -> f64 {
+    let product = a.distance(b) * b.distance(c) * c.distance(a);
+    if product == 0.0 {
+        return 0.0;
+    }
+
+    let cross = (b.x - a.x) * (c.y - a.y) - (b.y - a.y) * (c.x - a.x);
+    2.0 * cross.abs() / product
+}
+
+/// Returns how far along the path the robot is past the waypoint at `index`,
+/// which is negative if the robot hasn't reached it yet.
+fn progress_past(path: &[Waypoint], index: usize, position: Waypoint) -> f64 {
+    // Project the robot onto the segment after the waypoint, or the one before it at the end of the path.
+    let (start, end) = if index + 1 < path.len() {
+        (path[index], path[index + 1])
+    } else if index > 0 {
+        (path[index - 1], path[index])
+    } else {
+        return 0.0;
+    };
+
+    let (dx, dy) = (end.x - start.x, end.y - start.y);
+    let length = libm::hypot(dx, dy);
+    if length == 0.0 {
+        return 0.0;
+    }
+
+    let along = ((position.x - path[index].x) * dx + (position.y - path[index].y) * dy) / length;
+    if index + 1 < path.len() {
+        along.min(length)
+    } else {
+        along
+    }
+}
+
+/// Returns true if the robot has driven past the end of the path.
+fn passed_end(path: &[Waypoint], position: Waypoint) -> bool {
+    let [.., before, end] = path else {
+        return false;
+    };
+
+    let (dx, dy) = (end.x - before.x, end.y - before.y);
+    (end.x - position.x) * dx + (end.y - position.y) * dy < 0.0
+}
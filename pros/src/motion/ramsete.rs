@@ -0,0 +1,158 @@
+//! RAMSETE trajectory tracking.
+//!
+//! RAMSETE is a nonlinear controller that corrects the velocity and angular velocity of a
+//! [`Trajectory`] based on how far the robot has drifted away from it.
+//! The corrected velocities are turned into wheel voltages using a [`MotorFeedforward`] model,
+//! which makes trajectories very repeatable once the model is tuned.
+//!
+//! Example of following a trajectory:
+//! ```rust
+//! let controller = Ramsete::new(
+//!     Ramsete::DEFAULT_B,
+//!     Ramsete::DEFAULT_ZETA,
+//!     MotorFeedforward::new(0.5, 2.0, 0.3),
+//! );
+//! controller.follow(&drive, &odometry, &trajectory).await?;
+//! ```
+
+use super::{
+    run_then_brake,
+    trajectory::{Trajectory, TrajectorySample},
+    wrap_radians, ModeGuard, MotionError, MOTION_UPDATE_INTERVAL,
+};
+use crate::{
+    drivetrain::differential::DifferentialDrive,
+    feedforward::MotorFeedforward,
+    odometry::{Pose, PoseSource},
+    task::sleep,
+};
+
+/// A RAMSETE controller for differential drivetrains.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Ramsete {
+    /// Aggressiveness of the controller, similar to a proportional gain. Must be greater than zero.
+    ///
+    /// This gain depends on the distance units being used.
+    /// If distances are measured in inches instead of meters, it should be divided by 1550 (39.37 squared).
+    pub b: f64,
+    /// Damping of the controller. Must be between zero and one.
+    pub zeta: f64,
+    /// Model used to convert wheel velocities into voltages.
+    pub feedforward: MotorFeedforward,
+}
+
+impl Ramsete {
+    /// A good starting value for [`Ramsete::b`] when distances are in meters.
+    pub const DEFAULT_B: f64 = 2.0;
+    /// A good starting value for [`Ramsete::zeta`].
+    pub const DEFAULT_ZETA: f64 = 0.7;
+
+    /// Creates a new RAMSETE controller.
+    pub const fn new(b: f64, zeta: f64, feedforward: MotorFeedforward) -> Self {
+        Self {
+            b,
+            zeta,
+            feedforward,
+        }
+    }
+
+    /// Calculates the corrected linear and angular velocity needed to return to the reference sample.
+    ///
+    /// Returns a tuple of the linear velocity in distance units per second and the angular velocity
+    /// in radians per second (counterclockwise is positive).
+    pub fn calculate(&self, pose: Pose, reference: &TrajectorySample) -> (f64, f64) {
+        let (sin, cos) = (libm::sin(pose.heading), libm::cos(pose.heading));
+        let (dx, dy) = (reference.pose.x - pose.x, reference.pose.y - pose.y);
+
+        // Error in the robot's frame of reference.
+        let error_x = cos * dx + sin * dy;
+        let error_y = -sin * dx + cos * dy;
+        let error_heading = wrap_radians(reference.pose.heading - pose.heading);
+
+        let velocity = reference.velocity;
+        let angular_velocity = reference.angular_velocity();
+        let k = 2.0
+            * self.zeta
+            * libm::sqrt(angular_velocity * angular_velocity + self.b * velocity * velocity);
+
+        (
+            velocity * libm::cos(error_heading) + k * error_x,
+            angular_velocity
+                + k * error_heading
+                + self.b * velocity * sinc(error_heading) * error_y,
+        )
+    }
+
+    /// Follows a trajectory until its duration has elapsed.
+    ///
+    /// The drivetrain is braked when the motion finishes or is cancelled because the competition mode changed.
+    pub async fn follow(
+        &self,
+        drive: &DifferentialDrive,
+        pose_source: &impl PoseSource,
+        trajectory: &Trajectory,
+    ) -> Result<(), MotionError> {
+        run_then_brake(drive, self.follow_inner(drive, pose_source, trajectory)).await
+    }
+
+    async fn follow_inner(
+        &self,
+        drive: &DifferentialDrive,
+        pose_source: &impl PoseSource,
+        trajectory: &Trajectory,
+    ) -> Result<(), MotionError> {
+        let guard = ModeGuard::new();
+        let start = unsafe { pros_sys::millis() };
+        let half_track = drive.track_width() / 2.0;
+
+        let mut last_time = 0.0;
+        let mut last_reference = (0.0, 0.0);
+
+        loop {
+            guard.check()?;
+
+            let time = (unsafe { pros_sys::millis() } - start) as f64 / 1000.0;
+            if time > trajectory.duration() {
+                return Ok(());
+            }
+
+            let reference = trajectory.sample(time);
+            let (velocity, angular_velocity) = self.calculate(pose_source.pose(), &reference);
+
+            // Accelerations are taken from the reference so that feedback corrections don't cause spikes.
+            let reference_wheels = (
+                reference.velocity - reference.angular_velocity() * half_track,
+                reference.velocity + reference.angular_velocity() * half_track,
+            );
+            let dt = time - last_time;
+            let (left_acceleration, right_acceleration) = if dt > 0.0 {
+                (
+                    (reference_wheels.0 - last_reference.0) / dt,
+                    (reference_wheels.1 - last_reference.1) / dt,
+                )
+            } else {
+                (0.0, 0.0)
+            };
+            last_time = time;
+            last_reference = reference_wheels;
+
+            let left = velocity - angular_velocity * half_track;
+            let right = velocity + angular_velocity * half_track;
+            drive.set_voltages(
+                self.feedforward.calculate(left, left_acceleration) as f32,
+                self.feedforward.calculate(right, right_acceleration) as f32,
+            )?;
+
+            sleep(MOTION_UPDATE_INTERVAL).await;
+        }
+    }
+}
+
+/// Returns `sin(x) / x`, which approaches 1 as `x` approaches 0.
+fn sinc(x: f64) -> f64 {
+    if x.abs() < 1e-9 {
+        1.0
+    } else {
+        libm::sin(x) / x
+    }
+}
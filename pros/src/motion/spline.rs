@@ -0,0 +1,334 @@
+//! Splines for describing smooth paths.
+//!
+//! A spline is a curve parameterized from `t = 0` at its start to `t = 1` at its end.
+//! Paths made of several splines can be turned into [`Trajectory`](super::trajectory::Trajectory)s
+//! with [`Trajectory::generate`](super::trajectory::Trajectory::generate).
+//!
+//! Three kinds of splines are supported:
+//! - [`CubicBezier`] curves, which are shaped by two control points.
+//! - [`CubicHermite`] splines, which are shaped by the tangent at each end.
+//! - [`QuinticHermite`] splines, which are shaped by the tangent and second derivative at each end.
+//!   Chaining these gives paths with continuous curvature.
+//!
+//! Example of creating a path through several poses:
+//! ```rust
+//! let path = QuinticHermite::through_poses(&[
+//!     Pose::new(0.0, 0.0, 0.0),
+//!     Pose::new(24.0, 24.0, FRAC_PI_2),
+//!     Pose::new(0.0, 48.0, PI),
+//! ]);
+//! ```
+
+use alloc::vec::Vec;
+
+use super::Waypoint;
+use crate::odometry::Pose;
+
+/// A curve parameterized from `t = 0` to `t = 1`.
+pub trait Spline {
+    /// Returns the point on the spline at `t`.
+    fn position(&self, t: f64) -> Waypoint;
+
+    /// Returns the first derivative of the spline with respect to `t` as an `(x, y)` tuple.
+    fn derivative(&self, t: f64) -> (f64, f64);
+
+    /// Returns the second derivative of the spline with respect to `t` as an `(x, y)` tuple.
+    fn second_derivative(&self, t: f64) -> (f64, f64);
+
+    /// Returns the direction the spline is heading in at `t`, in radians counterclockwise from the x axis.
+    fn heading(&self, t: f64) -> f64 {
+        let (dx, dy) = self.derivative(t);
+        libm::atan2(dy, dx)
+    }
+
+    /// Returns the curvature of the spline at `t` (positive is counterclockwise).
+    fn curvature(&self, t: f64) -> f64 {
+        let (dx, dy) = self.derivative(t);
+        let (ddx, ddy) = self.second_derivative(t);
+        let speed_squared = dx * dx + dy * dy;
+        if speed_squared == 0.0 {
+            return 0.0;
+        }
+
+        (dx * ddy - dy * ddx) / (speed_squared * libm::sqrt(speed_squared))
+    }
+}
+
+impl<S: Spline + ?Sized> Spline for &S {
+    fn position(&self, t: f64) -> Waypoint {
+        (**self).position(t)
+    }
+
+    fn derivative(&self, t: f64) -> (f64, f64) {
+        (**self).derivative(t)
+    }
+
+    fn second_derivative(&self, t: f64) -> (f64, f64) {
+        (**self).second_derivative(t)
+    }
+}
+
+impl<S: Spline + ?Sized> Spline for alloc::boxed::Box<S> {
+    fn position(&self, t: f64) -> Waypoint {
+        (**self).position(t)
+    }
+
+    fn derivative(&self, t: f64) -> (f64, f64) {
+        (**self).derivative(t)
+    }
+
+    fn second_derivative(&self, t: f64) -> (f64, f64) {
+        (**self).second_derivative(t)
+    }
+}
+
+/// A pair of polynomials for x and y with `N` coefficients, from the constant term upwards.
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct Polynomial<const N: usize> {
+    x: [f64; N],
+    y: [f64; N],
+}
+
+impl<const N: usize> Polynomial<N> {
+    fn evaluate(coefficients: &[f64; N], t: f64) -> f64 {
+        coefficients
+            .iter()
+            .rev()
+            .fold(0.0, |acc, coefficient| acc * t + coefficient)
+    }
+
+    fn evaluate_derivative(coefficients: &[f64; N], t: f64) -> f64 {
+        coefficients
+            .iter()
+            .enumerate()
+            .skip(1)
+            .rev()
+            .fold(0.0, |acc, (power, coefficient)| {
+                acc * t + power as f64 * coefficient
+            })
+    }
+
+    fn evaluate_second_derivative(coefficients: &[f64; N], t: f64) -> f64 {
+        coefficients
+            .iter()
+            .enumerate()
+            .skip(2)
+            .rev()
+            .fold(0.0, |acc, (power, coefficient)| {
+                acc * t + (power * (power - 1)) as f64 * coefficient
+            })
+    }
+}
+
+impl<const N: usize> Spline for Polynomial<N> {
+    fn position(&self, t: f64) -> Waypoint {
+        Waypoint::new(Self::evaluate(&self.x, t), Self::evaluate(&self.y, t))
+    }
+
+    fn derivative(&self, t: f64) -> (f64, f64) {
+        (
+            Self::evaluate_derivative(&self.x, t),
+            Self::evaluate_derivative(&self.y, t),
+        )
+    }
+
+    fn second_derivative(&self, t: f64) -> (f64, f64) {
+        (
+            Self::evaluate_second_derivative(&self.x, t),
+            Self::evaluate_second_derivative(&self.y, t),
+        )
+    }
+}
+
+/// Implements [`Spline`] for a type by forwarding to its polynomial.
+macro_rules! impl_spline {
+    ($ty:ty) => {
+        impl Spline for $ty {
+            fn position(&self, t: f64) -> Waypoint {
+                self.polynomial.position(t)
+            }
+
+            fn derivative(&self, t: f64) -> (f64, f64) {
+                self.polynomial.derivative(t)
+            }
+
+            fn second_derivative(&self, t: f64) -> (f64, f64) {
+                self.polynomial.second_derivative(t)
+            }
+        }
+    };
+}
+
+/// Returns a tangent pointing along a pose's heading, scaled to the distance between two poses.
+///
+/// Scaling by the distance keeps the shape of the spline the same no matter how far apart its ends are.
+fn scaled_tangent(pose: Pose, start: Pose, end: Pose) -> (f64, f64) {
+    let scale = libm::hypot(end.x - start.x, end.y - start.y) * 1.2;
+    (
+        libm::cos(pose.heading) * scale,
+        libm::sin(pose.heading) * scale,
+    )
+}
+
+/// A cubic Bézier curve.
+///
+/// The curve starts at the first point and ends at the last point.
+/// It is pulled towards (but doesn't usually pass through) the two control points in between.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct CubicBezier {
+    polynomial: Polynomial<4>,
+}
+
+impl CubicBezier {
+    /// Creates a new cubic Bézier curve from its start, two control points, and end.
+    pub fn new(start: Waypoint, control_1: Waypoint, control_2: Waypoint, end: Waypoint) -> Self {
+        let coefficients = |p0: f64, p1: f64, p2: f64, p3: f64| {
+            [
+                p0,
+                3.0 * (p1 - p0),
+                3.0 * (p0 - 2.0 * p1 + p2),
+                -p0 + 3.0 * p1 - 3.0 * p2 + p3,
+            ]
+        };
+
+        Self {
+            polynomial: Polynomial {
+                x: coefficients(start.x, control_1.x, control_2.x, end.x),
+                y: coefficients(start.y, control_1.y, control_2.y, end.y),
+            },
+        }
+    }
+}
+
+impl_spline!(CubicBezier);
+
+/// A cubic Hermite spline.
+///
+/// The spline is defined by the position and tangent (first derivative) at each end.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct CubicHermite {
+    polynomial: Polynomial<4>,
+}
+
+impl CubicHermite {
+    /// Creates a new cubic Hermite spline from the position and tangent at each end.
+    pub fn new(
+        start: Waypoint,
+        start_tangent: (f64, f64),
+        end: Waypoint,
+        end_tangent: (f64, f64),
+    ) -> Self {
+        let coefficients = |p0: f64, m0: f64, p1: f64, m1: f64| {
+            [
+                p0,
+                m0,
+                -3.0 * p0 - 2.0 * m0 + 3.0 * p1 - m1,
+                2.0 * p0 + m0 - 2.0 * p1 + m1,
+            ]
+        };
+
+        Self {
+            polynomial: Polynomial {
+                x: coefficients(start.x, start_tangent.0, end.x, end_tangent.0),
+                y: coefficients(start.y, start_tangent.1, end.y, end_tangent.1),
+            },
+        }
+    }
+
+    /// Creates a cubic Hermite spline that leaves `start` and arrives at `end` along their headings.
+    pub fn from_poses(start: Pose, end: Pose) -> Self {
+        Self::new(
+            start.into(),
+            scaled_tangent(start, start, end),
+            end.into(),
+            scaled_tangent(end, start, end),
+        )
+    }
+
+    /// Creates a chain of cubic Hermite splines passing through every pose.
+    pub fn through_poses(poses: &[Pose]) -> Vec<Self> {
+        poses
+            .windows(2)
+            .map(|pair| Self::from_poses(pair[0], pair[1]))
+            .collect()
+    }
+}
+
+impl_spline!(CubicHermite);
+
+/// A quintic Hermite spline.
+///
+/// The spline is defined by the position, tangent (first derivative),
+/// and second derivative at each end.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct QuinticHermite {
+    polynomial: Polynomial<6>,
+}
+
+impl QuinticHermite {
+    /// Creates a new quintic Hermite spline from the position, tangent and second derivative at each end.
+    pub fn new(
+        start: Waypoint,
+        start_tangent: (f64, f64),
+        start_second_derivative: (f64, f64),
+        end: Waypoint,
+        end_tangent: (f64, f64),
+        end_second_derivative: (f64, f64),
+    ) -> Self {
+        let coefficients = |p0: f64, v0: f64, a0: f64, p1: f64, v1: f64, a1: f64| {
+            [
+                p0,
+                v0,
+                a0 / 2.0,
+                -10.0 * p0 - 6.0 * v0 - 1.5 * a0 + 0.5 * a1 - 4.0 * v1 + 10.0 * p1,
+                15.0 * p0 + 8.0 * v0 + 1.5 * a0 - a1 + 7.0 * v1 - 15.0 * p1,
+                -6.0 * p0 - 3.0 * v0 - 0.5 * a0 + 0.5 * a1 - 3.0 * v1 + 6.0 * p1,
+            ]
+        };
+
+        Self {
+            polynomial: Polynomial {
+                x: coefficients(
+                    start.x,
+                    start_tangent.0,
+                    start_second_derivative.0,
+                    end.x,
+                    end_tangent.0,
+                    end_second_derivative.0,
+                ),
+                y: coefficients(
+                    start.y,
+                    start_tangent.1,
+                    start_second_derivative.1,
+                    end.y,
+                    end_tangent.1,
+                    end_second_derivative.1,
+                ),
+            },
+        }
+    }
+
+    /// Creates a quintic Hermite spline that leaves `start` and arrives at `end` along their headings.
+    ///
+    /// The second derivatives at both ends are zero, so the robot is driving straight at each pose.
+    pub fn from_poses(start: Pose, end: Pose) -> Self {
+        Self::new(
+            start.into(),
+            scaled_tangent(start, start, end),
+            (0.0, 0.0),
+            end.into(),
+            scaled_tangent(end, start, end),
+            (0.0, 0.0),
+        )
+    }
+
+    /// Creates a chain of quintic Hermite splines passing through every pose.
+    pub fn through_poses(poses: &[Pose]) -> Vec<Self> {
+        poses
+            .windows(2)
+            .map(|pair| Self::from_poses(pair[0], pair[1]))
+            .collect()
+    }
+}
+
+impl_spline!(QuinticHermite);
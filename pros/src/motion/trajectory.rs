@@ -0,0 +1,348 @@
+//! Time-parameterized trajectories.
+//!
+//! A [`Trajectory`] describes where the robot should be at every point in time, along with the velocity
+//! and curvature it should be driving with. Trajectories are followed with a
+//! [`Ramsete`](super::ramsete::Ramsete) controller.
+//!
+//! Trajectories are usually generated on the brain from [`Spline`]s with [`Trajectory::generate`],
+//! which limits the velocity and acceleration of the robot to a set of [`TrajectoryConstraints`].
+//!
+//! Example of generating a trajectory through several poses:
+//! ```rust
+//! let path = QuinticHermite::through_poses(&[
+//!     Pose::new(0.0, 0.0, 0.0),
+//!     Pose::new(24.0, 24.0, FRAC_PI_2),
+//! ]);
+//! let trajectory = Trajectory::generate(&path, TrajectoryConstraints::new(40.0, 80.0));
+//! ```
+//!
+//! Generation doesn't depend on any hardware, so long trajectories can instead be generated when the
+//! program is compiled. A build script can depend on this crate (with the `no-link` feature of `pros-sys`,
+//! since it runs on the computer rather than the brain) and write the trajectory out as Rust source with
+//! [`Trajectory::write_static`], which the program then includes and loads with [`Trajectory::from_static`].
+//!
+//! Example of generating a trajectory in `build.rs`:
+//! ```rust
+//! use std::{env, fs, path::Path};
+//!
+//! use pros::{
+//!     motion::{spline::QuinticHermite, trajectory::{Trajectory, TrajectoryConstraints}},
+//!     odometry::Pose,
+//! };
+//!
+//! fn main() {
+//!     let path = QuinticHermite::through_poses(&[
+//!         Pose::new(0.0, 0.0, 0.0),
+//!         Pose::new(24.0, 24.0, core::f64::consts::FRAC_PI_2),
+//!     ]);
+//!     let trajectory = Trajectory::generate(&path, TrajectoryConstraints::new(40.0, 80.0));
+//!
+//!     let mut source = String::new();
+//!     trajectory.write_static("AUTON_TRAJECTORY", &mut source).unwrap();
+//!     let out_dir = env::var("OUT_DIR").unwrap();
+//!     fs::write(Path::new(&out_dir).join("auton_trajectory.rs"), source).unwrap();
+//! }
+//! ```
+//!
+//! And loading it in the program:
+//! ```rust
+//! include!(concat!(env!("OUT_DIR"), "/auton_trajectory.rs"));
+//!
+//! let trajectory = Trajectory::from_static(&AUTON_TRAJECTORY);
+//! ```
+
+use alloc::{borrow::Cow, vec::Vec};
+use core::fmt;
+
+use super::{spline::Spline, wrap_radians};
+use crate::odometry::Pose;
+
+/// Limits on how the robot can move along a generated trajectory.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct TrajectoryConstraints {
+    /// Maximum linear velocity of the robot.
+    pub max_velocity: f64,
+    /// Maximum linear acceleration (and deceleration) of the robot.
+    pub max_acceleration: f64,
+    /// Maximum centripetal acceleration of the robot, which limits its velocity around curves.
+    pub max_centripetal_acceleration: Option<f64>,
+    /// Velocity of the robot at the start of the trajectory.
+    pub start_velocity: f64,
+    /// Velocity of the robot at the end of the trajectory.
+    pub end_velocity: f64,
+    /// How many points each spline is split into when generating the trajectory.
+    pub samples_per_spline: usize,
+}
+
+impl TrajectoryConstraints {
+    /// Creates new constraints for a trajectory that starts and ends at rest.
+    ///
+    /// There is no centripetal acceleration limit by default, and each spline is split into 100 points.
+    pub const fn new(max_velocity: f64, max_acceleration: f64) -> Self {
+        Self {
+            max_velocity,
+            max_acceleration,
+            max_centripetal_acceleration: None,
+            start_velocity: 0.0,
+            end_velocity: 0.0,
+            samples_per_spline: 100,
+        }
+    }
+}
+
+/// The state of the robot at one point in time along a trajectory.
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub struct TrajectorySample {
+    /// Time since the start of the trajectory in seconds.
+    pub time: f64,
+    /// Where the robot should be.
+    pub pose: Pose,
+    /// Linear velocity of the robot in distance units per second.
+    pub velocity: f64,
+    /// Linear acceleration of the robot in distance units per second squared.
+    pub acceleration: f64,
+    /// Curvature of the path in radians per distance unit (positive is counterclockwise).
+    pub curvature: f64,
+}
+
+impl TrajectorySample {
+    /// Creates a new trajectory sample.
+    pub const fn new(
+        time: f64,
+        pose: Pose,
+        velocity: f64,
+        acceleration: f64,
+        curvature: f64,
+    ) -> Self {
+        Self {
+            time,
+            pose,
+            velocity,
+            acceleration,
+            curvature,
+        }
+    }
+
+    /// Returns the angular velocity of the robot in radians per second.
+    pub fn angular_velocity(&self) -> f64 {
+        self.velocity * self.curvature
+    }
+
+    /// Linearly interpolates between two samples.
+    fn lerp(&self, other: &Self, t: f64) -> Self {
+        let lerp = |a: f64, b: f64| a + (b - a) * t;
+        Self {
+            time: lerp(self.time, other.time),
+            pose: Pose {
+                x: lerp(self.pose.x, other.pose.x),
+                y: lerp(self.pose.y, other.pose.y),
+                heading: self.pose.heading
+                    + wrap_radians(other.pose.heading - self.pose.heading) * t,
+            },
+            velocity: lerp(self.velocity, other.velocity),
+            acceleration: lerp(self.acceleration, other.acceleration),
+            curvature: lerp(self.curvature, other.curvature),
+        }
+    }
+}
+
+/// A list of samples sorted by time.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Trajectory {
+    samples: Cow<'static, [TrajectorySample]>,
+}
+
+impl Trajectory {
+    /// Creates a new trajectory from samples sorted by time.
+    pub fn new(samples: Vec<TrajectorySample>) -> Self {
+        Self {
+            samples: Cow::Owned(samples),
+        }
+    }
+
+    /// Creates a new trajectory from static samples sorted by time without copying them.
+    pub const fn from_static(samples: &'static [TrajectorySample]) -> Self {
+        Self {
+            samples: Cow::Borrowed(samples),
+        }
+    }
+
+    /// Generates a trajectory that follows a chain of splines as quickly as the constraints allow.
+    ///
+    /// Each spline should start where the previous one ended.
+    pub fn generate<S: Spline>(splines: &[S], constraints: TrajectoryConstraints) -> Self {
+        // Split the splines into points with their distance along the path and curvature.
+        let mut points: Vec<(Pose, f64, f64)> = Vec::new();
+        let resolution = constraints.samples_per_spline.max(1);
+        let mut distance = 0.0;
+
+        for (i, spline) in splines.iter().enumerate() {
+            // Every spline after the first starts where the last one ended, so skip its first point.
+            let first = if i == 0 { 0 } else { 1 };
+            for step in first..=resolution {
+                let t = step as f64 / resolution as f64;
+                let position = spline.position(t);
+                if let Some((last, ..)) = points.last() {
+                    distance += libm::hypot(position.x - last.x, position.y - last.y);
+                }
+
+                points.push((
+                    Pose::new(position.x, position.y, spline.heading(t)),
+                    distance,
+                    spline.curvature(t),
+                ));
+            }
+        }
+
+        // Find the fastest each point can be driven through on its own.
+        let mut velocities: Vec<f64> = points
+            .iter()
+            .map(
+                |&(.., curvature)| match constraints.max_centripetal_acceleration {
+                    Some(max) if curvature != 0.0 => constraints
+                        .max_velocity
+                        .min(libm::sqrt(max / curvature.abs())),
+                    _ => constraints.max_velocity,
+                },
+            )
+            .collect();
+
+        // Limit acceleration going forwards and deceleration going backwards.
+        if let Some(first) = velocities.first_mut() {
+            *first = first.min(constraints.start_velocity);
+        }
+        for i in 1..points.len() {
+            let gap = points[i].1 - points[i - 1].1;
+            let reachable = libm::sqrt(
+                velocities[i - 1] * velocities[i - 1] + 2.0 * constraints.max_acceleration * gap,
+            );
+            velocities[i] = velocities[i].min(reachable);
+        }
+        if let Some(last) = velocities.last_mut() {
+            *last = last.min(constraints.end_velocity);
+        }
+        for i in (0..points.len().saturating_sub(1)).rev() {
+            let gap = points[i + 1].1 - points[i].1;
+            let reachable = libm::sqrt(
+                velocities[i + 1] * velocities[i + 1] + 2.0 * constraints.max_acceleration * gap,
+            );
+            velocities[i] = velocities[i].min(reachable);
+        }
+
+        // Work out how long it takes to drive between each point assuming constant acceleration.
+        let mut samples: Vec<TrajectorySample> = Vec::with_capacity(points.len());
+        let mut time = 0.0;
+        for (i, &(pose, distance, curvature)) in points.iter().enumerate() {
+            let velocity = velocities[i];
+            let mut acceleration = 0.0;
+
+            if i > 0 {
+                let gap = distance - points[i - 1].1;
+                let last_velocity = velocities[i - 1];
+                if gap > 0.0 {
+                    acceleration =
+                        (velocity * velocity - last_velocity * last_velocity) / (2.0 * gap);
+                    time += if last_velocity + velocity > 0.0 {
+                        2.0 * gap / (last_velocity + velocity)
+                    } else {
+                        libm::sqrt(2.0 * gap / constraints.max_acceleration)
+                    };
+                }
+
+                // Acceleration applies to the segment before this point, so store it on the previous sample too.
+                if let Some(previous) = samples.last_mut() {
+                    previous.acceleration = acceleration;
+                }
+            }
+
+            samples.push(TrajectorySample::new(
+                time,
+                pose,
+                velocity,
+                acceleration,
+                curvature,
+            ));
+        }
+
+        Self::new(samples)
+    }
+
+    /// Returns every sample in the trajectory.
+    pub fn samples(&self) -> &[TrajectorySample] {
+        &self.samples
+    }
+
+    /// Writes the trajectory as the Rust source of a `static` array of samples named `name`,
+    /// which can be loaded with [`Trajectory::from_static`].
+    ///
+    /// The source refers to this crate as `pros`. Values that aren't finite, such as the infinite curvature
+    /// of turning in place, are written as the matching `f64` constant.
+    pub fn write_static(&self, name: &str, out: &mut impl fmt::Write) -> fmt::Result {
+        writeln!(
+            out,
+            "pub static {name}: [::pros::motion::trajectory::TrajectorySample; {}] = [",
+            self.samples.len()
+        )?;
+        for sample in self.samples.iter() {
+            writeln!(
+                out,
+                "    ::pros::motion::trajectory::TrajectorySample::new({}, ::pros::odometry::Pose::new({}, {}, {}), {}, {}, {}),",
+                F64Literal(sample.time),
+                F64Literal(sample.pose.x),
+                F64Literal(sample.pose.y),
+                F64Literal(sample.pose.heading),
+                F64Literal(sample.velocity),
+                F64Literal(sample.acceleration),
+                F64Literal(sample.curvature),
+            )?;
+        }
+        writeln!(out, "];")
+    }
+
+    /// Returns how long the trajectory takes to follow in seconds.
+    pub fn duration(&self) -> f64 {
+        self.samples.last().map_or(0.0, |sample| sample.time)
+    }
+
+    /// Returns the state the robot should be in at the given time, interpolating between samples.
+    ///
+    /// Times before the start or after the end of the trajectory return the first or last sample.
+    /// Empty trajectories return a default sample.
+    pub fn sample(&self, time: f64) -> TrajectorySample {
+        let samples = &*self.samples;
+        let (Some(first), Some(last)) = (samples.first(), samples.last()) else {
+            return TrajectorySample::default();
+        };
+        if time <= first.time {
+            return *first;
+        }
+        if time >= last.time {
+            return *last;
+        }
+
+        // Index of the first sample after `time`. This is never 0 because of the checks above.
+        let next = samples.partition_point(|sample| sample.time <= time);
+        let (before, after) = (&samples[next - 1], &samples[next]);
+        let span = after.time - before.time;
+        if span <= 0.0 {
+            return *after;
+        }
+
+        before.lerp(after, (time - before.time) / span)
+    }
+}
+
+/// Formats an `f64` as a Rust literal that evaluates to the same value.
+struct F64Literal(f64);
+
+impl fmt::Display for F64Literal {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.0 {
+            value if value.is_nan() => f.write_str("f64::NAN"),
+            f64::INFINITY => f.write_str("f64::INFINITY"),
+            f64::NEG_INFINITY => f.write_str("f64::NEG_INFINITY"),
+            // Debug formatting always includes a decimal point or exponent, so the value stays an `f64` literal.
+            value => write!(f, "{value:?}"),
+        }
+    }
+}
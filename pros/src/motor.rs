@@ -23,8 +23,10 @@ use pros_sys::{PROS_ERR, PROS_ERR_F};
 use snafu::Snafu;
 
 use crate::{
+    devices::{validate_port, DeviceType, SmartDevice, SmartPort},
     error::{bail_on, map_errno, PortError},
     position::Position,
+    sensors::Encoder,
 };
 
 /// The basic motor struct.
@@ -33,10 +35,11 @@ pub struct Motor {
     port: u8,
 }
 
-//TODO: Implement good set_velocity and get_velocity functions.
 //TODO: Measure the number of counts per rotation. Fow now we assume it is 4096
 impl Motor {
-    pub fn new(port: u8, brake_mode: BrakeMode) -> Result<Self, MotorError> {
+    pub fn new(port: SmartPort, brake_mode: BrakeMode) -> Result<Self, MotorError> {
+        let port = port.index();
+        validate_port(port, DeviceType::Motor)?;
         unsafe {
             bail_on!(
                 PROS_ERR,
@@ -100,6 +103,17 @@ impl Motor {
         Ok(())
     }
 
+    /// Sets the velocity of the motor in RPM.
+    ///
+    /// The motor's internal velocity controller will try to maintain this velocity.
+    /// The maximum velocity depends on the motor's [`Gearset`].
+    pub fn set_velocity(&self, rpm: i32) -> Result<(), MotorError> {
+        unsafe {
+            bail_on!(PROS_ERR, pros_sys::motor_move_velocity(self.port, rpm));
+        }
+        Ok(())
+    }
+
     /// Moves the motor to an absolute position, based off of the last motor zeroing.
     /// units for the velocity is RPM.
     pub fn set_position_absolute(
@@ -132,6 +146,16 @@ impl Motor {
         Ok(())
     }
 
+    /// Returns the actual velocity of the motor in RPM.
+    pub fn velocity(&self) -> Result<f64, MotorError> {
+        unsafe {
+            Ok(bail_on!(
+                PROS_ERR_F,
+                pros_sys::motor_get_actual_velocity(self.port)
+            ))
+        }
+    }
+
     /// Returns the power drawn by the motor in Watts.
     pub fn power(&self) -> Result<f64, MotorError> {
         unsafe { Ok(bail_on!(PROS_ERR_F, pros_sys::motor_get_power(self.port))) }
@@ -224,6 +248,28 @@ impl Motor {
     }
 }
 
+impl SmartDevice for Motor {
+    fn port_index(&self) -> u8 {
+        self.port
+    }
+
+    fn device_type(&self) -> DeviceType {
+        DeviceType::Motor
+    }
+}
+
+impl Encoder for Motor {
+    type Error = MotorError;
+
+    fn position(&self) -> Result<Position, MotorError> {
+        Motor::position(self)
+    }
+
+    fn zero(&mut self) -> Result<(), MotorError> {
+        Motor::zero(self)
+    }
+}
+
 /// Determines how a motor should act when braking.
 pub enum BrakeMode {
     /// Motor never brakes.
@@ -287,6 +333,15 @@ impl Gearset {
     pub const RPM_200: Gearset = Gearset::Green;
     /// 600 rpm
     pub const RPM_600: Gearset = Gearset::Blue;
+
+    /// Returns the maximum speed of a motor with this gearset in RPM.
+    pub const fn max_rpm(&self) -> f64 {
+        match self {
+            Gearset::Red => 100.0,
+            Gearset::Green => 200.0,
+            Gearset::Blue => 600.0,
+        }
+    }
 }
 
 impl From<i32> for Gearset {
@@ -0,0 +1,388 @@
+//! Tracking wheel odometry.
+//!
+//! Odometry estimates the [`Pose`] of the robot on the field by integrating the distance travelled by
+//! tracking wheels along with the heading reported by a [`Gyro`] such as an [`InertialSensor`](crate::sensors::imu::InertialSensor).
+//! Tracking wheels can use any [`Encoder`], including rotation sensors and motors.
+//!
+//! An [`Odometry`] tracker can either be updated manually with [`Odometry::update`],
+//! or moved into its own task with [`Odometry::spawn`], which returns an [`OdometryHandle`]
+//! that can be used to read or reset the pose from anywhere.
+//!
+//! Example of tracking the robot's pose in the background:
+//! ```rust
+//! let peripherals = Peripherals::take().unwrap();
+//! let vertical = TrackingWheel::new(RotationSensor::new(peripherals.port_1, false)?, 2.75, 0.0);
+//! let horizontal = TrackingWheel::new(RotationSensor::new(peripherals.port_2, false)?, 2.75, -3.5);
+//! let imu = InertialSensor::new(peripherals.port_3)?;
+//! let odometry = Odometry::new(vertical, Some(horizontal), imu)?
+//!     .spawn(Duration::from_millis(10));
+//!
+//! loop {
+//!     let pose = odometry.pose();
+//!     println!("x: {}, y: {}, heading: {}", pose.x, pose.y, pose.heading);
+//!     sleep(Duration::from_millis(100)).await;
+//! }
+//! ```
+
+use core::{
+    f64::consts::PI,
+    sync::atomic::{AtomicBool, Ordering},
+    time::Duration,
+};
+
+use alloc::{boxed::Box, sync::Arc};
+use snafu::Snafu;
+
+use crate::{
+    adi::AdiError,
+    error::PortError,
+    motor::MotorError,
+    position::Position,
+    sensors::{gps::GpsError, imu::InertialError, Encoder, Gyro},
+    sync::Mutex,
+    task::{self, Interval},
+};
+
+/// The position and orientation of the robot on the field.
+///
+/// The heading is measured in radians counterclockwise from the positive x axis,
+/// so a robot at a heading of zero is facing along the x axis.
+/// Distances are in the same units that were used to describe the tracking wheels.
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub struct Pose {
+    pub x: f64,
+    pub y: f64,
+    pub heading: f64,
+}
+
+impl Pose {
+    /// Creates a new pose.
+    pub const fn new(x: f64, y: f64, heading: f64) -> Self {
+        Self { x, y, heading }
+    }
+}
+
+/// Anything that can report the current pose of the robot.
+///
+/// This is implemented for [`OdometryHandle`], [`PoseEstimator`](crate::pose_estimator::PoseEstimator)
+/// (and a [`Mutex`] around either) as well as any closure returning a [`Pose`],
+/// so motion controllers can be used with custom localization code.
+pub trait PoseSource {
+    /// Returns the current pose of the robot.
+    fn pose(&self) -> Pose;
+}
+
+impl<F: Fn() -> Pose> PoseSource for F {
+    fn pose(&self) -> Pose {
+        self()
+    }
+}
+
+impl<T: PoseSource> PoseSource for Mutex<T> {
+    fn pose(&self) -> Pose {
+        self.lock().pose()
+    }
+}
+
+/// An [`Encoder`] with its errors converted into [`OdometryError`]s, so that different encoders can be stored together.
+trait TrackingEncoder: Send {
+    fn position(&self) -> Result<Position, OdometryError>;
+}
+
+impl<E> TrackingEncoder for E
+where
+    E: Encoder + Send,
+    OdometryError: From<E::Error>,
+{
+    fn position(&self) -> Result<Position, OdometryError> {
+        Ok(Encoder::position(self)?)
+    }
+}
+
+/// A [`Gyro`] with its errors converted into [`OdometryError`]s.
+trait TrackingGyro: Send {
+    fn rotation(&self) -> Result<f64, OdometryError>;
+}
+
+impl<G> TrackingGyro for G
+where
+    G: Gyro + Send,
+    OdometryError: From<G::Error>,
+{
+    fn rotation(&self) -> Result<f64, OdometryError> {
+        Ok(Gyro::rotation(self)?)
+    }
+}
+
+/// An unpowered wheel used to measure how far the robot has travelled.
+pub struct TrackingWheel {
+    encoder: Box<dyn TrackingEncoder>,
+    wheel_diameter: f64,
+    offset: f64,
+    last_distance: f64,
+}
+
+impl TrackingWheel {
+    /// Creates a new tracking wheel.
+    ///
+    /// `offset` is the distance from the wheel to the robot's tracking center, measured perpendicular to the direction the wheel rolls in.
+    /// For wheels that roll forward it is positive when the wheel is to the right of the tracking center,
+    /// and for wheels that roll sideways it is positive when the wheel is in front of the tracking center.
+    pub fn new<E>(encoder: E, wheel_diameter: f64, offset: f64) -> Self
+    where
+        E: Encoder + Send + 'static,
+        OdometryError: From<E::Error>,
+    {
+        Self {
+            encoder: Box::new(encoder),
+            wheel_diameter,
+            offset,
+            last_distance: 0.0,
+        }
+    }
+
+    /// Returns the total distance the wheel has travelled.
+    pub fn distance(&self) -> Result<f64, OdometryError> {
+        Ok(self.encoder.position()?.into_degrees() / 360.0 * PI * self.wheel_diameter)
+    }
+
+    /// Returns the distance travelled since the last reading, given the wheel's new total distance.
+    fn delta(&mut self, distance: f64) -> f64 {
+        let delta = distance - self.last_distance;
+        self.last_distance = distance;
+        delta
+    }
+}
+
+/// Sensor readings taken together for a single update.
+struct Readings {
+    /// Heading reported by the gyro, in radians counterclockwise without the heading offset.
+    gyro_heading: f64,
+    vertical: f64,
+    horizontal: Option<f64>,
+}
+
+struct OdometryState {
+    pose: Pose,
+    /// Set when the pose was changed from outside of the tracker.
+    rebase: bool,
+}
+
+/// Tracks the pose of the robot using tracking wheels and a gyro.
+///
+/// A wheel rolling forward is required, while a wheel rolling sideways is optional.
+/// Without a sideways wheel, the robot is assumed to never slide sideways.
+pub struct Odometry {
+    vertical: TrackingWheel,
+    horizontal: Option<TrackingWheel>,
+    gyro: Box<dyn TrackingGyro>,
+    heading_offset: f64,
+    last_heading: f64,
+    state: Arc<Mutex<OdometryState>>,
+}
+
+impl Odometry {
+    /// Creates a new odometry tracker starting at the origin.
+    ///
+    /// The gyro is usually an [`InertialSensor`](crate::sensors::imu::InertialSensor), but anything implementing [`Gyro`] can be used.
+    pub fn new<G>(
+        vertical: TrackingWheel,
+        horizontal: Option<TrackingWheel>,
+        gyro: G,
+    ) -> Result<Self, OdometryError>
+    where
+        G: Gyro + Send + 'static,
+        OdometryError: From<G::Error>,
+    {
+        let mut odometry = Self {
+            vertical,
+            horizontal,
+            gyro: Box::new(gyro),
+            heading_offset: 0.0,
+            last_heading: 0.0,
+            state: Arc::new(Mutex::new(OdometryState {
+                pose: Pose::default(),
+                rebase: false,
+            })),
+        };
+        odometry.rebase(Pose::default())?;
+        Ok(odometry)
+    }
+
+    /// Returns the latest pose of the robot.
+    pub fn pose(&self) -> Pose {
+        self.state.lock().pose
+    }
+
+    /// Sets the pose of the robot.
+    pub fn set_pose(&mut self, pose: Pose) -> Result<(), OdometryError> {
+        self.rebase(pose)
+    }
+
+    /// Reads the sensors and integrates the movement of the robot since the last update.
+    ///
+    /// This should be called at a fixed rate, preferably every 10 milliseconds.
+    pub fn update(&mut self) -> Result<Pose, OdometryError> {
+        // Every sensor is read before anything is changed, so a failed read doesn't lose movement
+        // and the pose isn't locked while waiting on the sensors.
+        let readings = self.read_sensors()?;
+
+        let state = self.state.clone();
+        let mut state = state.lock();
+
+        if state.rebase {
+            self.rebase_from(state.pose, &readings);
+            state.rebase = false;
+            return Ok(state.pose);
+        }
+
+        let heading = self.heading_offset + readings.gyro_heading;
+        let delta_heading = heading - self.last_heading;
+        self.last_heading = heading;
+
+        let delta_vertical = self.vertical.delta(readings.vertical);
+        let delta_horizontal = match (&mut self.horizontal, readings.horizontal) {
+            (Some(wheel), Some(distance)) => wheel.delta(distance),
+            _ => 0.0,
+        };
+
+        // Find the movement of the tracking center in the robot's frame.
+        // If the robot turned, it moved along an arc, so the chord of that arc is used.
+        let (forward, right) = if delta_heading.abs() < 1e-9 {
+            (delta_vertical, delta_horizontal)
+        } else {
+            let chord = 2.0 * libm::sin(delta_heading / 2.0);
+            let horizontal_offset = self.horizontal.as_ref().map_or(0.0, |wheel| wheel.offset);
+            (
+                chord * (delta_vertical / delta_heading - self.vertical.offset),
+                chord * (delta_horizontal / delta_heading + horizontal_offset),
+            )
+        };
+
+        // The chord points halfway between the old and new headings.
+        let average_heading = heading - delta_heading / 2.0;
+        let (sin, cos) = (libm::sin(average_heading), libm::cos(average_heading));
+
+        state.pose = Pose {
+            x: state.pose.x + forward * cos + right * sin,
+            y: state.pose.y + forward * sin - right * cos,
+            heading,
+        };
+        Ok(state.pose)
+    }
+
+    /// Moves the tracker into a new task that updates it every `interval`.
+    ///
+    /// Errors that occur while updating (such as a sensor being unplugged) are ignored and tracking resumes
+    /// once the sensors can be read again.
+    pub fn spawn(mut self, interval: Duration) -> OdometryHandle {
+        let handle = OdometryHandle {
+            state: self.state.clone(),
+            stopped: Arc::new(AtomicBool::new(false)),
+        };
+
+        let stopped = handle.stopped.clone();
+        task::Builder::new()
+            .name("odometry")
+            .spawn(move || {
+                let mut timer = Interval::start();
+                while !stopped.load(Ordering::Relaxed) {
+                    _ = self.update();
+                    timer.delay(interval);
+                }
+            })
+            .expect("Failed to spawn odometry task");
+
+        handle
+    }
+
+    fn read_sensors(&self) -> Result<Readings, OdometryError> {
+        Ok(Readings {
+            // The gyro measures degrees clockwise.
+            gyro_heading: -self.gyro.rotation()?.to_radians(),
+            vertical: self.vertical.distance()?,
+            horizontal: self
+                .horizontal
+                .as_ref()
+                .map(TrackingWheel::distance)
+                .transpose()?,
+        })
+    }
+
+    fn rebase(&mut self, pose: Pose) -> Result<(), OdometryError> {
+        let readings = self.read_sensors()?;
+        let state = self.state.clone();
+        let mut state = state.lock();
+        state.pose = pose;
+        self.rebase_from(pose, &readings);
+        state.rebase = false;
+        Ok(())
+    }
+
+    /// Makes the given pose the new starting point for integration.
+    fn rebase_from(&mut self, pose: Pose, readings: &Readings) {
+        self.heading_offset = pose.heading - readings.gyro_heading;
+        self.last_heading = pose.heading;
+        self.vertical.delta(readings.vertical);
+        if let (Some(wheel), Some(distance)) = (&mut self.horizontal, readings.horizontal) {
+            wheel.delta(distance);
+        }
+    }
+}
+
+/// A handle to an odometry tracker running in its own task.
+///
+/// Handles are cheap to clone and can be shared between tasks.
+#[derive(Clone)]
+pub struct OdometryHandle {
+    state: Arc<Mutex<OdometryState>>,
+    stopped: Arc<AtomicBool>,
+}
+
+impl OdometryHandle {
+    /// Returns the latest pose of the robot.
+    pub fn pose(&self) -> Pose {
+        self.state.lock().pose
+    }
+
+    /// Sets the pose of the robot.
+    ///
+    /// The new pose takes effect immediately and the tracker continues integrating from it.
+    pub fn set_pose(&self, pose: Pose) {
+        let mut state = self.state.lock();
+        state.pose = pose;
+        state.rebase = true;
+    }
+
+    /// Resets the pose of the robot to the origin.
+    pub fn reset(&self) {
+        self.set_pose(Pose::default());
+    }
+
+    /// Stops the tracking task.
+    ///
+    /// The last pose will continue to be available after the task stops.
+    pub fn stop(&self) {
+        self.stopped.store(true, Ordering::Relaxed);
+    }
+}
+
+impl PoseSource for OdometryHandle {
+    fn pose(&self) -> Pose {
+        OdometryHandle::pose(self)
+    }
+}
+
+#[derive(Debug, Snafu)]
+pub enum OdometryError {
+    #[snafu(display("{source}"), context(false))]
+    Port { source: PortError },
+    #[snafu(display("{source}"), context(false))]
+    Motor { source: MotorError },
+    #[snafu(display("{source}"), context(false))]
+    Inertial { source: InertialError },
+    #[snafu(display("{source}"), context(false))]
+    Gps { source: GpsError },
+    #[snafu(display("{source}"), context(false))]
+    Adi { source: AdiError },
+}
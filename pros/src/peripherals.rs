@@ -0,0 +1,115 @@
+//! Ownership of the brain's ports.
+//!
+//! Every device needs a port, and two devices can't share one.
+//! [`Peripherals::take`] hands out one [`SmartPort`] for each smart port and one [`AdiPort`] for each three-wire port,
+//! and device constructors take ownership of the port they are given.
+//! This makes creating two devices on the same port a compile-time error,
+//! and taking the peripherals twice fails at startup.
+//!
+//! Example of creating devices from the peripherals:
+//! ```rust
+//! let peripherals = Peripherals::take().unwrap();
+//! let motor = Motor::new(peripherals.port_1, BrakeMode::Brake)?;
+//! let imu = InertialSensor::new(peripherals.port_2)?;
+//! ```
+
+use core::sync::atomic::{AtomicBool, Ordering};
+
+use crate::{adi::AdiPort, devices::SmartPort};
+
+static PERIPHERALS_TAKEN: AtomicBool = AtomicBool::new(false);
+
+/// Every port on the brain.
+#[derive(Debug)]
+pub struct Peripherals {
+    pub port_1: SmartPort,
+    pub port_2: SmartPort,
+    pub port_3: SmartPort,
+    pub port_4: SmartPort,
+    pub port_5: SmartPort,
+    pub port_6: SmartPort,
+    pub port_7: SmartPort,
+    pub port_8: SmartPort,
+    pub port_9: SmartPort,
+    pub port_10: SmartPort,
+    pub port_11: SmartPort,
+    pub port_12: SmartPort,
+    pub port_13: SmartPort,
+    pub port_14: SmartPort,
+    pub port_15: SmartPort,
+    pub port_16: SmartPort,
+    pub port_17: SmartPort,
+    pub port_18: SmartPort,
+    pub port_19: SmartPort,
+    pub port_20: SmartPort,
+    pub port_21: SmartPort,
+
+    pub adi_a: AdiPort,
+    pub adi_b: AdiPort,
+    pub adi_c: AdiPort,
+    pub adi_d: AdiPort,
+    pub adi_e: AdiPort,
+    pub adi_f: AdiPort,
+    pub adi_g: AdiPort,
+    pub adi_h: AdiPort,
+}
+
+impl Peripherals {
+    const unsafe fn new() -> Self {
+        unsafe {
+            Self {
+                port_1: SmartPort::new(1),
+                port_2: SmartPort::new(2),
+                port_3: SmartPort::new(3),
+                port_4: SmartPort::new(4),
+                port_5: SmartPort::new(5),
+                port_6: SmartPort::new(6),
+                port_7: SmartPort::new(7),
+                port_8: SmartPort::new(8),
+                port_9: SmartPort::new(9),
+                port_10: SmartPort::new(10),
+                port_11: SmartPort::new(11),
+                port_12: SmartPort::new(12),
+                port_13: SmartPort::new(13),
+                port_14: SmartPort::new(14),
+                port_15: SmartPort::new(15),
+                port_16: SmartPort::new(16),
+                port_17: SmartPort::new(17),
+                port_18: SmartPort::new(18),
+                port_19: SmartPort::new(19),
+                port_20: SmartPort::new(20),
+                port_21: SmartPort::new(21),
+
+                adi_a: AdiPort::new_unchecked(1),
+                adi_b: AdiPort::new_unchecked(2),
+                adi_c: AdiPort::new_unchecked(3),
+                adi_d: AdiPort::new_unchecked(4),
+                adi_e: AdiPort::new_unchecked(5),
+                adi_f: AdiPort::new_unchecked(6),
+                adi_g: AdiPort::new_unchecked(7),
+                adi_h: AdiPort::new_unchecked(8),
+            }
+        }
+    }
+
+    /// Takes every port on the brain.
+    ///
+    /// Returns `None` if the peripherals have already been taken.
+    pub fn take() -> Option<Self> {
+        if PERIPHERALS_TAKEN.swap(true, Ordering::AcqRel) {
+            None
+        } else {
+            Some(unsafe { Self::new() })
+        }
+    }
+
+    /// Creates the peripherals even if they have already been taken.
+    ///
+    /// # Safety
+    ///
+    /// Ports that are already being used by devices must not be used again.
+    pub unsafe fn steal() -> Self {
+        PERIPHERALS_TAKEN.store(true, Ordering::Release);
+        unsafe { Self::new() }
+    }
+}
@@ -20,7 +20,7 @@ pub struct PidController {
     pub kd: f32,
 
     last_time: i32,
-    last_position: f32,
+    last_position: Option<f32>,
     i: f32,
 }
 
@@ -31,11 +31,20 @@ impl PidController {
             ki,
             kd,
             last_time: 0,
-            last_position: 0.0,
+            last_position: None,
             i: 0.0,
         }
     }
 
+    /// Clears the accumulated integral and derivative history.
+    ///
+    /// This should be called before reusing the controller for a new setpoint.
+    pub fn reset(&mut self) {
+        self.last_time = unsafe { pros_sys::clock() };
+        self.last_position = None;
+        self.i = 0.0;
+    }
+
     pub fn update(&mut self, setpoint: f32, position: f32) -> f32 {
         let time = unsafe { pros_sys::clock() };
         let mut delta_time = (time - self.last_time) as f32 / pros_sys::CLOCKS_PER_SEC as f32;
@@ -49,14 +58,19 @@ impl PidController {
         let p = self.kp * error;
         let i = self.ki * self.i;
 
-        let mut d = (position - self.last_position) / delta_time;
+        // The derivative is taken on the measurement rather than the error
+        // so that changing the setpoint doesn't cause a spike in the output.
+        let mut d = match self.last_position {
+            Some(last_position) => -self.kd * (position - last_position) / delta_time,
+            None => 0.0,
+        };
         if d.is_nan() {
             d = 0.0
         }
 
         let output = p + i + d;
 
-        self.last_position = position;
+        self.last_position = Some(position);
         self.last_time = time;
 
         output
@@ -0,0 +1,363 @@
+//! Pose estimation by fusing odometry with absolute sensors.
+//!
+//! Odometry is smooth and accurate over short distances but slowly drifts, while the VEX GPS sensor
+//! never drifts but is noisy (especially near the field walls) and can drop out entirely when it can't see
+//! the field strips. A [`PoseEstimator`] combines the two with an extended Kalman filter:
+//! odometry is used to predict how the robot moved, and each GPS reading pulls the estimate towards it
+//! depending on how much the filter currently trusts each source.
+//!
+//! - GPS readings are weighted by [`GpsSensor::rms_error`]. Readings with an error above
+//!   [`PoseEstimatorConfig::max_gps_error`] or that disagree wildly with the current estimate are
+//!   ignored, so dropouts and glitches don't throw the estimate off.
+//! - GPS readings arrive slightly late, so they are shifted forwards by the odometry movement that
+//!   happened during [`PoseEstimatorConfig::gps_latency`].
+//! - [`InertialSensor`] headings can optionally be fused as well, which is useful when the odometry
+//!   doesn't have its own inertial sensor (for example, when using drivetrain motor encoders).
+//!
+//! Every update method that doesn't read hardware takes a timestamp in milliseconds,
+//! so the filter can be driven with recorded data.
+//!
+//! Example of fusing tracking wheel odometry with a GPS sensor:
+//! ```rust
+//! let odometry = Odometry::new(vertical, None, imu)?.spawn(Duration::from_millis(10));
+//! let mut estimator = PoseEstimator::new(Pose::default(), PoseEstimatorConfig::default());
+//!
+//! loop {
+//!     estimator.update_odometry(odometry.pose(), unsafe { pros_sys::millis() });
+//!     estimator.update_gps(&gps)?;
+//!     sleep(Duration::from_millis(10)).await;
+//! }
+//! ```
+
+use alloc::collections::VecDeque;
+use core::time::Duration;
+
+use snafu::Snafu;
+
+use crate::{
+    motion::wrap_radians,
+    odometry::{Pose, PoseSource},
+    sensors::{
+        gps::{GpsError, GpsSensor},
+        imu::{InertialError, InertialSensor},
+    },
+};
+
+/// How long odometry history is kept for latency compensation, in milliseconds.
+const HISTORY_LENGTH: u32 = 1000;
+
+/// Tuning for a [`PoseEstimator`].
+///
+/// Distances are in the units of the odometry (inches by default), and angles are in radians.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PoseEstimatorConfig {
+    /// Standard deviation of the x and y of the starting pose given to [`PoseEstimator::new`].
+    ///
+    /// This decides how far the first GPS readings can pull the estimate, so it should be large
+    /// when the robot's starting position isn't known.
+    pub initial_position_std_dev: f64,
+    /// Standard deviation of the heading of the starting pose given to [`PoseEstimator::new`].
+    pub initial_heading_std_dev: f64,
+    /// Standard deviation of odometry position drift per unit of distance travelled.
+    pub translation_drift: f64,
+    /// Standard deviation of odometry heading drift per radian turned.
+    pub rotation_drift: f64,
+    /// Standard deviation of headings read from an inertial sensor.
+    pub imu_std_dev: f64,
+    /// Number of distance units in a meter, which is used to convert GPS readings.
+    pub gps_units_per_meter: f64,
+    /// GPS readings with an RMS error above this (in meters) are ignored.
+    pub max_gps_error: f64,
+    /// How old GPS readings are by the time they are read.
+    pub gps_latency: Duration,
+    /// Readings further than this many standard deviations from the current estimate are rejected as outliers.
+    pub outlier_threshold: f64,
+}
+
+impl Default for PoseEstimatorConfig {
+    /// Returns a configuration for odometry measured in inches.
+    fn default() -> Self {
+        Self {
+            initial_position_std_dev: 6.0,
+            initial_heading_std_dev: 0.1,
+            translation_drift: 0.02,
+            rotation_drift: 0.02,
+            imu_std_dev: 0.01,
+            gps_units_per_meter: 39.37,
+            max_gps_error: 0.1,
+            gps_latency: Duration::from_millis(20),
+            outlier_threshold: 4.0,
+        }
+    }
+}
+
+/// An extended Kalman filter that fuses odometry, inertial sensor and GPS readings into one pose.
+#[derive(Debug, Clone)]
+pub struct PoseEstimator {
+    config: PoseEstimatorConfig,
+    pose: Pose,
+    covariance: [[f64; 3]; 3],
+    last_odometry: Option<Pose>,
+    imu_offset: Option<f64>,
+    history: VecDeque<(u32, Pose)>,
+    last_position_update: Option<u32>,
+}
+
+impl PoseEstimator {
+    /// Creates a new pose estimator starting at the given pose.
+    ///
+    /// The pose is trusted as much as [`PoseEstimatorConfig::initial_position_std_dev`] and
+    /// [`PoseEstimatorConfig::initial_heading_std_dev`] allow.
+    pub fn new(initial_pose: Pose, config: PoseEstimatorConfig) -> Self {
+        Self {
+            config,
+            pose: initial_pose,
+            covariance: diagonal_covariance(
+                config.initial_position_std_dev,
+                config.initial_heading_std_dev,
+            ),
+            last_odometry: None,
+            imu_offset: None,
+            history: VecDeque::new(),
+            last_position_update: None,
+        }
+    }
+
+    /// Returns the current estimate of the robot's pose.
+    pub fn pose(&self) -> Pose {
+        self.pose
+    }
+
+    /// Returns the covariance of the estimate, in the order x, y, heading.
+    pub fn covariance(&self) -> [[f64; 3]; 3] {
+        self.covariance
+    }
+
+    /// Returns the time (in milliseconds) that the last accepted position measurement was taken, if any.
+    ///
+    /// This can be used to detect when the GPS has dropped out for a long time.
+    pub fn last_position_update(&self) -> Option<u32> {
+        self.last_position_update
+    }
+
+    /// Moves the estimate to a new pose, with the given standard deviations of its position and heading.
+    ///
+    /// A standard deviation of 0 trusts the pose completely, so later readings can only correct it once
+    /// the robot has moved.
+    pub fn set_pose(&mut self, pose: Pose, position_std_dev: f64, heading_std_dev: f64) {
+        self.pose = pose;
+        self.covariance = diagonal_covariance(position_std_dev, heading_std_dev);
+        self.imu_offset = None;
+        self.history.clear();
+    }
+
+    /// Predicts the robot's movement from a new odometry reading.
+    ///
+    /// Only the change in the odometry pose since the last reading is used,
+    /// so the odometry can use any starting pose.
+    pub fn update_odometry(&mut self, odometry: Pose, timestamp: u32) {
+        let Some(last) = self.last_odometry.replace(odometry) else {
+            self.history.push_back((timestamp, odometry));
+            return;
+        };
+
+        // Movement in the frame of the robot at the last reading.
+        let (forward, left) = to_local(last, odometry.x - last.x, odometry.y - last.y);
+        let turn = wrap_radians(odometry.heading - last.heading);
+
+        let (sin, cos) = (libm::sin(self.pose.heading), libm::cos(self.pose.heading));
+        self.pose = Pose {
+            x: self.pose.x + forward * cos - left * sin,
+            y: self.pose.y + forward * sin + left * cos,
+            heading: wrap_radians(self.pose.heading + turn),
+        };
+
+        // Covariance grows with how far the robot moved.
+        let jacobian = [
+            [1.0, 0.0, -forward * sin - left * cos],
+            [0.0, 1.0, forward * cos - left * sin],
+            [0.0, 0.0, 1.0],
+        ];
+        let distance = libm::hypot(forward, left);
+        let translation_noise = self.config.translation_drift * distance;
+        let rotation_noise = self.config.rotation_drift * turn.abs()
+            + self.config.translation_drift * distance / 10.0;
+
+        self.covariance = multiply_transpose(multiply(jacobian, self.covariance), jacobian);
+        self.covariance[0][0] += translation_noise * translation_noise;
+        self.covariance[1][1] += translation_noise * translation_noise;
+        self.covariance[2][2] += rotation_noise * rotation_noise;
+
+        self.history.push_back((timestamp, odometry));
+        while self
+            .history
+            .front()
+            .is_some_and(|&(time, _)| timestamp.wrapping_sub(time) > HISTORY_LENGTH)
+        {
+            self.history.pop_front();
+        }
+    }
+
+    /// Corrects the estimate with a heading measurement in radians counterclockwise from the x axis.
+    pub fn update_heading(&mut self, heading: f64, std_dev: f64) {
+        let innovation = wrap_radians(heading - self.pose.heading);
+        self.scalar_update([0.0, 0.0, 1.0], innovation, std_dev * std_dev);
+    }
+
+    /// Corrects the estimate with a position measurement taken at `measured_at` (in milliseconds).
+    ///
+    /// The measurement is shifted forwards by the odometry movement since it was taken.
+    /// Returns false if the measurement was rejected as an outlier. Outliers are only rejected once a
+    /// measurement has been accepted, so that an estimator started at the wrong pose can still find the robot.
+    pub fn update_position(&mut self, x: f64, y: f64, std_dev: f64, measured_at: u32) -> bool {
+        let (x, y) = self.compensate_latency(x, y, measured_at);
+        let variance = std_dev * std_dev;
+        let innovation = [x - self.pose.x, y - self.pose.y];
+
+        // Mahalanobis distance of the measurement from the estimate.
+        let s = [
+            [self.covariance[0][0] + variance, self.covariance[0][1]],
+            [self.covariance[1][0], self.covariance[1][1] + variance],
+        ];
+        let determinant = s[0][0] * s[1][1] - s[0][1] * s[1][0];
+        if determinant > 0.0 && self.last_position_update.is_some() {
+            let distance_squared = (s[1][1] * innovation[0] * innovation[0]
+                - (s[0][1] + s[1][0]) * innovation[0] * innovation[1]
+                + s[0][0] * innovation[1] * innovation[1])
+                / determinant;
+            if distance_squared > self.config.outlier_threshold * self.config.outlier_threshold {
+                return false;
+            }
+        }
+
+        // The x and y noise is independent, so the measurements can be applied one at a time.
+        self.scalar_update([1.0, 0.0, 0.0], x - self.pose.x, variance);
+        self.scalar_update([0.0, 1.0, 0.0], y - self.pose.y, variance);
+        self.last_position_update = Some(measured_at);
+        true
+    }
+
+    /// Reads an inertial sensor and corrects the estimate's heading.
+    ///
+    /// The first reading after creating the estimator (or [setting the pose](Self::set_pose))
+    /// only lines the sensor up with the current heading.
+    pub fn update_imu(&mut self, imu: &InertialSensor) -> Result<(), PoseEstimatorError> {
+        // The inertial sensor measures clockwise rotation in degrees.
+        let heading = -imu.rotation()?.to_radians();
+        match self.imu_offset {
+            Some(offset) => self.update_heading(heading + offset, self.config.imu_std_dev),
+            None => self.imu_offset = Some(self.pose.heading - heading),
+        }
+        Ok(())
+    }
+
+    /// Reads a GPS sensor and corrects the estimate's position.
+    ///
+    /// The GPS and the estimate must share a coordinate system, with the origin at the center of the field.
+    /// Returns false if the reading was ignored because its error was too high or it was an outlier.
+    pub fn update_gps(&mut self, gps: &GpsSensor) -> Result<bool, PoseEstimatorError> {
+        let error = gps.rms_error()?;
+        if error > self.config.max_gps_error {
+            return Ok(false);
+        }
+
+        let status = gps.status()?;
+        let measured_at =
+            unsafe { pros_sys::millis() }.wrapping_sub(self.config.gps_latency.as_millis() as u32);
+        let scale = self.config.gps_units_per_meter;
+
+        Ok(self.update_position(
+            status.x * scale,
+            status.y * scale,
+            error * scale,
+            measured_at,
+        ))
+    }
+
+    /// Applies a Kalman update for a single measurement of `h · state`.
+    fn scalar_update(&mut self, h: [f64; 3], innovation: f64, variance: f64) {
+        let p = self.covariance;
+        let ph = [
+            p[0][0] * h[0] + p[0][1] * h[1] + p[0][2] * h[2],
+            p[1][0] * h[0] + p[1][1] * h[1] + p[1][2] * h[2],
+            p[2][0] * h[0] + p[2][1] * h[1] + p[2][2] * h[2],
+        ];
+        let s = h[0] * ph[0] + h[1] * ph[1] + h[2] * ph[2] + variance;
+        if s <= 0.0 {
+            return;
+        }
+
+        let gain = [ph[0] / s, ph[1] / s, ph[2] / s];
+        self.pose.x += gain[0] * innovation;
+        self.pose.y += gain[1] * innovation;
+        self.pose.heading = wrap_radians(self.pose.heading + gain[2] * innovation);
+
+        // P = P - K (H P), where H P is the transpose of P Hᵀ since P is symmetric.
+        for (row, gain) in self.covariance.iter_mut().zip(gain) {
+            for (value, ph) in row.iter_mut().zip(ph) {
+                *value -= gain * ph;
+            }
+        }
+    }
+
+    /// Shifts a position measured in the past forwards by the odometry movement since then.
+    fn compensate_latency(&self, x: f64, y: f64, measured_at: u32) -> (f64, f64) {
+        let (Some(&(_, latest)), Some(&(_, then))) = (
+            self.history.back(),
+            self.history
+                .iter()
+                .rev()
+                .find(|&&(time, _)| (measured_at.wrapping_sub(time) as i32) >= 0)
+                .or(self.history.front()),
+        ) else {
+            return (x, y);
+        };
+
+        let (forward, left) = to_local(then, latest.x - then.x, latest.y - then.y);
+        let heading_then = self.pose.heading - wrap_radians(latest.heading - then.heading);
+        let (sin, cos) = (libm::sin(heading_then), libm::cos(heading_then));
+        (
+            x + forward * cos - left * sin,
+            y + forward * sin + left * cos,
+        )
+    }
+}
+
+impl PoseSource for PoseEstimator {
+    fn pose(&self) -> Pose {
+        self.pose
+    }
+}
+
+/// Returns a covariance with independent x, y and heading errors.
+fn diagonal_covariance(position_std_dev: f64, heading_std_dev: f64) -> [[f64; 3]; 3] {
+    let position_variance = position_std_dev * position_std_dev;
+    [
+        [position_variance, 0.0, 0.0],
+        [0.0, position_variance, 0.0],
+        [0.0, 0.0, heading_std_dev * heading_std_dev],
+    ]
+}
+
+/// Rotates a field-relative offset into the frame of a robot at `pose`.
+fn to_local(pose: Pose, dx: f64, dy: f64) -> (f64, f64) {
+    let (sin, cos) = (libm::sin(pose.heading), libm::cos(pose.heading));
+    (dx * cos + dy * sin, -dx * sin + dy * cos)
+}
+
+fn multiply(a: [[f64; 3]; 3], b: [[f64; 3]; 3]) -> [[f64; 3]; 3] {
+    core::array::from_fn(|i| core::array::from_fn(|j| (0..3).map(|k| a[i][k] * b[k][j]).sum()))
+}
+
+/// Returns `a · bᵀ`.
+fn multiply_transpose(a: [[f64; 3]; 3], b: [[f64; 3]; 3]) -> [[f64; 3]; 3] {
+    core::array::from_fn(|i| core::array::from_fn(|j| (0..3).map(|k| a[i][k] * b[j][k]).sum()))
+}
+
+#[derive(Debug, Snafu)]
+pub enum PoseEstimatorError {
+    #[snafu(display("{source}"), context(false))]
+    Inertial { source: InertialError },
+    #[snafu(display("{source}"), context(false))]
+    Gps { source: GpsError },
+}
@@ -6,7 +6,12 @@ use core::ffi::c_double;
 
 use pros_sys::PROS_ERR;
 
-use crate::error::{bail_on, PortError};
+use crate::{
+    devices::{validate_port, DeviceType, SmartDevice, SmartPort},
+    error::{bail_on, PortError},
+};
+
+use super::RangeFinder;
 
 /// A physical distance sensor plugged into a port.
 /// Distance sensors can only keep track of one object at a time.
@@ -15,7 +20,9 @@ pub struct DistanceSensor {
 }
 
 impl DistanceSensor {
-    pub fn new(port: u8) -> Result<Self, PortError> {
+    pub fn new(port: SmartPort) -> Result<Self, PortError> {
+        let port = port.index();
+        validate_port(port, DeviceType::Distance)?;
         let sensor = Self { port };
         sensor.distance()?;
         Ok(sensor)
@@ -46,3 +53,21 @@ impl DistanceSensor {
         Ok(confidence * 100.0 / 63.0)
     }
 }
+
+impl SmartDevice for DistanceSensor {
+    fn port_index(&self) -> u8 {
+        self.port
+    }
+
+    fn device_type(&self) -> DeviceType {
+        DeviceType::Distance
+    }
+}
+
+impl RangeFinder for DistanceSensor {
+    type Error = PortError;
+
+    fn distance(&self) -> Result<f64, PortError> {
+        Ok(DistanceSensor::distance(self)? as f64)
+    }
+}
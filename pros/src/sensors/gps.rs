@@ -2,11 +2,21 @@
 //!
 //! A notable differenc between this API and that of PROS
 //! is that [`GpsSensor::status`] returns acceleration along with other status data.
+//!
+//! Positions are [`GpsPosition`]s in meters with `(0, 0)` at the center of the field,
+//! and headings are in degrees clockwise from north on the field.
+
+use core::time::Duration;
 
 use pros_sys::{PROS_ERR, PROS_ERR_F};
 use snafu::Snafu;
 
-use crate::error::{bail_on, map_errno, PortError};
+use crate::{
+    devices::{validate_port, DeviceType, SmartDevice, SmartPort},
+    error::{bail_on, map_errno, PortError},
+};
+
+use super::Gyro;
 
 /// Represents the data output from a GPS sensor.
 pub struct GpsStatus {
@@ -22,29 +32,125 @@ pub struct GpsStatus {
     pub accel_z: f64,
 }
 
-// A physical GPS sensor plugged into a port.
+/// A position on the field in meters, with `(0, 0)` at the center of the field.
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub struct GpsPosition {
+    pub x: f64,
+    pub y: f64,
+}
+
+impl GpsPosition {
+    /// Creates a new field position in meters.
+    pub const fn new(x: f64, y: f64) -> Self {
+        Self { x, y }
+    }
+}
+
+/// The position of a GPS sensor relative to the robot's center of turning, in meters.
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub struct GpsOffset {
+    pub x: f64,
+    pub y: f64,
+}
+
+impl GpsOffset {
+    /// Creates a new mounting offset in meters.
+    pub const fn new(x: f64, y: f64) -> Self {
+        Self { x, y }
+    }
+}
+
+/// Raw readings from the gyroscope or accelerometer built into a GPS sensor.
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub struct GpsRaw {
+    pub x: f64,
+    pub y: f64,
+    pub z: f64,
+}
+
+/// A physical GPS sensor plugged into a port.
 pub struct GpsSensor {
     port: u8,
 }
 
 impl GpsSensor {
     /// Creates a new GPS sensor on the given port.
-    pub fn new(port: u8) -> Result<Self, GpsError> {
+    pub fn new(port: SmartPort) -> Result<Self, GpsError> {
+        Self::with_initial_pose(port, GpsPosition::default(), 0.0, GpsOffset::default())
+    }
+
+    /// Creates a new GPS sensor on the given port, starting at a known position and heading.
+    ///
+    /// `heading` is in degrees clockwise from north on the field,
+    /// and `offset` is where the sensor is mounted relative to the robot's center of turning.
+    /// The initial pose is used until the sensor can see the field strips.
+    pub fn with_initial_pose(
+        port: SmartPort,
+        position: GpsPosition,
+        heading: f64,
+        offset: GpsOffset,
+    ) -> Result<Self, GpsError> {
+        let port = port.index();
+        validate_port(port, DeviceType::Gps)?;
         unsafe {
             bail_on!(
                 PROS_ERR,
-                pros_sys::gps_initialize_full(port, 0.0, 0.0, 0.0, 0.0, 0.0)
+                pros_sys::gps_initialize_full(
+                    port, position.x, position.y, heading, offset.x, offset.y
+                )
             );
         }
 
         Ok(Self { port })
     }
 
-    /// Sets the offset of the GPS sensor, relative to the sensor of turning, in meters.
-    pub fn set_offset(&self, x: f64, y: f64) {
+    /// Sets the offset of the GPS sensor relative to the robot's center of turning, in meters.
+    pub fn set_offset(&self, offset: GpsOffset) -> Result<(), GpsError> {
+        unsafe {
+            bail_on!(
+                PROS_ERR,
+                pros_sys::gps_set_offset(self.port, offset.x, offset.y)
+            );
+        }
+        Ok(())
+    }
+
+    /// Gets the offset of the GPS sensor relative to the robot's center of turning, in meters.
+    pub fn offset(&self) -> Result<GpsOffset, GpsError> {
+        let mut offset = GpsOffset::default();
+        unsafe {
+            bail_on!(
+                PROS_ERR,
+                pros_sys::gps_get_offset(self.port, &mut offset.x, &mut offset.y)
+            );
+        }
+        Ok(offset)
+    }
+
+    /// Sets the position and heading of the robot.
+    ///
+    /// `heading` is in degrees clockwise from north on the field.
+    pub fn set_position(&self, position: GpsPosition, heading: f64) -> Result<(), GpsError> {
+        unsafe {
+            bail_on!(
+                PROS_ERR,
+                pros_sys::gps_set_position(self.port, position.x, position.y, heading)
+            );
+        }
+        Ok(())
+    }
+
+    /// Sets how often the built-in inertial sensor sends new data.
+    ///
+    /// The minimum interval is 5 milliseconds.
+    pub fn set_data_rate(&self, interval: Duration) -> Result<(), GpsError> {
         unsafe {
-            pros_sys::gps_set_offset(self.port, x, y);
+            bail_on!(
+                PROS_ERR,
+                pros_sys::gps_set_data_rate(self.port, (interval.as_millis() as u32).max(5))
+            );
         }
+        Ok(())
     }
 
     /// Gets the possible error of the GPS sensor, in meters.
@@ -76,6 +182,60 @@ impl GpsSensor {
         }
     }
 
+    /// Gets the position of the robot on the field in meters.
+    pub fn position(&self) -> Result<GpsPosition, GpsError> {
+        let status = unsafe { pros_sys::gps_get_status(self.port) };
+        bail_on!(PROS_ERR_F, status.x);
+        Ok(GpsPosition::new(status.x, status.y))
+    }
+
+    /// Gets the heading of the robot in degrees, from 0 to 360.
+    ///
+    /// Clockwise rotations are represented with positive degree values, and 0 is north on the field.
+    pub fn heading(&self) -> Result<f64, GpsError> {
+        Ok(unsafe { bail_on!(PROS_ERR_F, pros_sys::gps_get_heading(self.port)) })
+    }
+
+    /// Gets the heading of the robot in degrees without wrapping it between 0 and 360.
+    pub fn heading_raw(&self) -> Result<f64, GpsError> {
+        Ok(unsafe { bail_on!(PROS_ERR_F, pros_sys::gps_get_heading_raw(self.port)) })
+    }
+
+    /// Gets the total rotation of the robot in degrees since it was last zeroed.
+    pub fn rotation(&self) -> Result<f64, GpsError> {
+        Ok(unsafe { bail_on!(PROS_ERR_F, pros_sys::gps_get_rotation(self.port)) })
+    }
+
+    /// Sets the total rotation of the robot in degrees.
+    pub fn set_rotation(&self, rotation: f64) -> Result<(), GpsError> {
+        unsafe {
+            bail_on!(PROS_ERR, pros_sys::gps_set_rotation(self.port, rotation));
+        }
+        Ok(())
+    }
+
+    /// Gets the angular velocity reported by the built-in gyroscope, in degrees per second.
+    pub fn gyro_rate(&self) -> Result<GpsRaw, GpsError> {
+        let rate = unsafe { pros_sys::gps_get_gyro_rate(self.port) };
+        bail_on!(PROS_ERR_F, rate.x);
+        Ok(GpsRaw {
+            x: rate.x,
+            y: rate.y,
+            z: rate.z,
+        })
+    }
+
+    /// Gets the acceleration reported by the built-in accelerometer, in Gs.
+    pub fn accel(&self) -> Result<GpsRaw, GpsError> {
+        let accel = unsafe { pros_sys::gps_get_accel(self.port) };
+        bail_on!(PROS_ERR_F, accel.x);
+        Ok(GpsRaw {
+            x: accel.x,
+            y: accel.y,
+            z: accel.z,
+        })
+    }
+
     /// Zeroes the rotation of the GPS sensor.
     pub fn zero_rotation(&self) -> Result<(), GpsError> {
         unsafe {
@@ -85,6 +245,28 @@ impl GpsSensor {
     }
 }
 
+impl SmartDevice for GpsSensor {
+    fn port_index(&self) -> u8 {
+        self.port
+    }
+
+    fn device_type(&self) -> DeviceType {
+        DeviceType::Gps
+    }
+}
+
+impl Gyro for GpsSensor {
+    type Error = GpsError;
+
+    fn heading(&self) -> Result<f64, GpsError> {
+        GpsSensor::heading(self)
+    }
+
+    fn rotation(&self) -> Result<f64, GpsError> {
+        GpsSensor::rotation(self)
+    }
+}
+
 #[derive(Debug, Snafu)]
 pub enum GpsError {
     #[snafu(display("GPS sensor is still calibrating."))]
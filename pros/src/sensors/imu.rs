@@ -6,7 +6,12 @@ use core::{
 use pros_sys::{PROS_ERR, PROS_ERR_F};
 use snafu::Snafu;
 
-use crate::error::{bail_on, map_errno, take_errno, FromErrno, PortError};
+use crate::{
+    devices::{validate_port, DeviceType, SmartDevice, SmartPort},
+    error::{bail_on, map_errno, take_errno, FromErrno, PortError},
+};
+
+use super::Gyro;
 
 pub const IMU_RESET_TIMEOUT: Duration = Duration::from_secs(3);
 pub const IMU_MIN_DATA_RATE: Duration = Duration::from_millis(5);
@@ -19,7 +24,9 @@ pub struct InertialSensor {
 
 impl InertialSensor {
     /// Create a new inertial sensor from a smart port index.
-    pub fn new(port: u8) -> Result<Self, InertialError> {
+    pub fn new(port: SmartPort) -> Result<Self, InertialError> {
+        let port = port.index();
+        validate_port(port, DeviceType::Imu)?;
         let sensor = Self { port };
         sensor.status()?;
         Ok(sensor)
@@ -247,6 +254,28 @@ impl InertialSensor {
     }
 }
 
+impl SmartDevice for InertialSensor {
+    fn port_index(&self) -> u8 {
+        self.port
+    }
+
+    fn device_type(&self) -> DeviceType {
+        DeviceType::Imu
+    }
+}
+
+impl Gyro for InertialSensor {
+    type Error = InertialError;
+
+    fn heading(&self) -> Result<f64, InertialError> {
+        InertialSensor::heading(self)
+    }
+
+    fn rotation(&self) -> Result<f64, InertialError> {
+        InertialSensor::rotation(self)
+    }
+}
+
 /// Standard quaternion consisting of a vector defining an axis of rotation
 /// and a rotation value about the axis.
 #[derive(Default, Debug, Clone, Copy, PartialEq)]
@@ -1,9 +1,12 @@
 //! Physical sensors on the VEX V5.
 //!
-//! Most sensors can be created with a `new` function that generally takes a port number along with other sensor specific parameters.
-//! Multiple sensors can be created on the same port as long as they are all the same sensor type.
+//! Most sensors can be created with a `new` function that generally takes a [`SmartPort`](crate::devices::SmartPort)
+//! along with other sensor specific parameters.
 //! All sensors are thread safe.
 //!
+//! Sensors that measure the same kind of thing implement the same capability trait ([`Encoder`], [`Gyro`] or [`RangeFinder`]),
+//! so code like [odometry](crate::odometry) can work with any of them.
+//!
 //! In cases where PROS gives the option of a blocking or non-blocking API,
 //! the blocking API is used for a synchronous method and the non-blocking API is used to create a future.
 //!
@@ -14,9 +17,73 @@
 //! - [`Distance`](distance::DistanceSensor)
 //! - [`Vision`](vision::VisionSensor)
 //! - [`GPS`](gps::GpsSensor)
+//! - [`Optical`](optical::OpticalSensor)
 
 pub mod distance;
 pub mod gps;
 pub mod imu;
+pub mod optical;
 pub mod rotation;
+pub mod stream;
 pub mod vision;
+
+use core::time::Duration;
+
+use self::stream::{sample_stream, SampleStream};
+use crate::position::Position;
+
+/// A sensor that measures how far something has rotated, such as a tracking wheel or a motor.
+pub trait Encoder {
+    type Error;
+
+    /// Returns the total amount the encoder has rotated since it was last zeroed.
+    fn position(&self) -> Result<Position, Self::Error>;
+
+    /// Sets the current position of the encoder to zero.
+    fn zero(&mut self) -> Result<(), Self::Error>;
+
+    /// Returns a stream of the encoder's position sampled every `interval`.
+    fn position_stream(
+        &self,
+        interval: Duration,
+    ) -> SampleStream<impl FnMut() -> Result<Position, Self::Error> + '_> {
+        sample_stream(interval, || self.position())
+    }
+}
+
+/// A sensor that measures which direction the robot is facing.
+///
+/// Angles are in degrees, and clockwise rotations are positive.
+pub trait Gyro {
+    type Error;
+
+    /// Returns the heading of the robot, from 0 to 360 degrees.
+    fn heading(&self) -> Result<f64, Self::Error>;
+
+    /// Returns the total amount the robot has rotated, without wrapping around.
+    fn rotation(&self) -> Result<f64, Self::Error>;
+
+    /// Returns a stream of the robot's heading sampled every `interval`.
+    fn heading_stream(
+        &self,
+        interval: Duration,
+    ) -> SampleStream<impl FnMut() -> Result<f64, Self::Error> + '_> {
+        sample_stream(interval, || self.heading())
+    }
+}
+
+/// A sensor that measures the distance to the nearest object in front of it.
+pub trait RangeFinder {
+    type Error;
+
+    /// Returns the distance to the nearest object in millimeters.
+    fn distance(&self) -> Result<f64, Self::Error>;
+
+    /// Returns a stream of the distance to the nearest object sampled every `interval`.
+    fn distance_stream(
+        &self,
+        interval: Duration,
+    ) -> SampleStream<impl FnMut() -> Result<f64, Self::Error> + '_> {
+        sample_stream(interval, || self.distance())
+    }
+}
@@ -0,0 +1,287 @@
+//! Optical sensor device.
+//!
+//! The optical sensor measures the color and proximity of nearby objects, and can detect simple hand
+//! gestures. Color and proximity readings are not available while gesture detection is enabled.
+//!
+//! Example of checking the color of a game object:
+//! ```rust
+//! let optical = OpticalSensor::new(peripherals.port_1, false)?;
+//! optical.set_led_pwm(100)?;
+//! if optical.proximity()? > 0.5 && optical.hue()? < 30.0 {
+//!     println!("Red object in front of the sensor");
+//! }
+//! ```
+
+use core::time::Duration;
+
+use pros_sys::{PROS_ERR, PROS_ERR_F};
+use snafu::Snafu;
+
+use crate::{
+    devices::{validate_port, DeviceType, SmartDevice, SmartPort},
+    error::{bail_on, map_errno, PortError},
+};
+
+/// The smallest integration time the sensor supports.
+pub const MIN_INTEGRATION_TIME: Duration = Duration::from_millis(3);
+/// The largest integration time the sensor supports.
+pub const MAX_INTEGRATION_TIME: Duration = Duration::from_millis(712);
+
+/// A physical optical sensor plugged into a port.
+pub struct OpticalSensor {
+    port: u8,
+    gesture_detection_enabled: bool,
+}
+
+impl OpticalSensor {
+    /// Creates a new optical sensor on the given port.
+    /// Whether gesture detection should be enabled can be specified.
+    pub fn new(port: SmartPort, gesture_detection_enabled: bool) -> Result<Self, OpticalError> {
+        let port = port.index();
+        validate_port(port, DeviceType::Optical)?;
+        let mut sensor = Self {
+            port,
+            gesture_detection_enabled,
+        };
+
+        if gesture_detection_enabled {
+            sensor.enable_gesture_detection()?;
+        } else {
+            sensor.disable_gesture_detection()?;
+        }
+
+        Ok(sensor)
+    }
+
+    /// Returns the detected color hue in degrees, from 0 to 360.
+    ///
+    /// Hue is not available while gesture detection is enabled.
+    pub fn hue(&self) -> Result<f64, OpticalError> {
+        Ok(unsafe { bail_on!(PROS_ERR_F, pros_sys::optical_get_hue(self.port)) })
+    }
+
+    /// Returns the detected color saturation, from 0 to 1.
+    ///
+    /// Saturation is not available while gesture detection is enabled.
+    pub fn saturation(&self) -> Result<f64, OpticalError> {
+        Ok(unsafe { bail_on!(PROS_ERR_F, pros_sys::optical_get_saturation(self.port)) })
+    }
+
+    /// Returns the detected color brightness, from 0 to 1.
+    ///
+    /// Brightness is not available while gesture detection is enabled.
+    pub fn brightness(&self) -> Result<f64, OpticalError> {
+        Ok(unsafe { bail_on!(PROS_ERR_F, pros_sys::optical_get_brightness(self.port)) })
+    }
+
+    /// Returns how close an object is to the sensor, from 0 (far away) to 1 (touching the sensor).
+    ///
+    /// Proximity is not available while gesture detection is enabled.
+    pub fn proximity(&self) -> Result<f64, OpticalError> {
+        let proximity = unsafe { bail_on!(PROS_ERR, pros_sys::optical_get_proximity(self.port)) };
+        Ok(proximity as f64 / 255.0)
+    }
+
+    /// Returns the processed red, green and blue values along with the brightness.
+    pub fn rgb(&self) -> Result<OpticalRgb, OpticalError> {
+        let rgb = unsafe { pros_sys::optical_get_rgb(self.port) };
+        bail_on!(PROS_ERR_F, rgb.red);
+
+        Ok(OpticalRgb {
+            red: rgb.red,
+            green: rgb.green,
+            blue: rgb.blue,
+            brightness: rgb.brightness,
+        })
+    }
+
+    /// Returns the raw, unprocessed red, green, blue and clear channel readings.
+    pub fn raw(&self) -> Result<OpticalRaw, OpticalError> {
+        let raw = unsafe { pros_sys::optical_get_raw(self.port) };
+        bail_on!(PROS_ERR as u32, raw.clear);
+
+        Ok(OpticalRaw {
+            red: raw.red,
+            green: raw.green,
+            blue: raw.blue,
+            clear: raw.clear,
+        })
+    }
+
+    /// Sets the brightness of the sensor's white LED, from 0 to 100 percent.
+    pub fn set_led_pwm(&self, value: u8) -> Result<(), OpticalError> {
+        if value > 100 {
+            return Err(OpticalError::InvalidLedPwm);
+        }
+        unsafe {
+            bail_on!(PROS_ERR, pros_sys::optical_set_led_pwm(self.port, value));
+        }
+        Ok(())
+    }
+
+    /// Returns the brightness of the sensor's white LED, from 0 to 100 percent.
+    pub fn led_pwm(&self) -> Result<u8, OpticalError> {
+        Ok(unsafe { bail_on!(PROS_ERR, pros_sys::optical_get_led_pwm(self.port)) } as u8)
+    }
+
+    /// Sets how long the sensor collects light for each reading.
+    ///
+    /// Shorter integration times update faster, while longer ones are more accurate in low light.
+    /// The integration time must be between [`MIN_INTEGRATION_TIME`] and [`MAX_INTEGRATION_TIME`].
+    /// The default is 100 milliseconds.
+    pub fn set_integration_time(&self, time: Duration) -> Result<(), OpticalError> {
+        if !(MIN_INTEGRATION_TIME..=MAX_INTEGRATION_TIME).contains(&time) {
+            return Err(OpticalError::InvalidIntegrationTime);
+        }
+        unsafe {
+            bail_on!(
+                PROS_ERR,
+                pros_sys::optical_set_integration_time(self.port, time.as_secs_f64() * 1000.0)
+            );
+        }
+        Ok(())
+    }
+
+    /// Returns how long the sensor collects light for each reading.
+    pub fn integration_time(&self) -> Result<Duration, OpticalError> {
+        // This returns PROS_ERR rather than PROS_ERR_F on failure.
+        let time = unsafe {
+            bail_on!(
+                PROS_ERR as f64,
+                pros_sys::optical_get_integration_time(self.port)
+            )
+        };
+        Ok(Duration::from_secs_f64(time / 1000.0))
+    }
+
+    /// Enables gesture detection.
+    ///
+    /// Color and proximity readings are not available while gesture detection is enabled.
+    pub fn enable_gesture_detection(&mut self) -> Result<(), OpticalError> {
+        unsafe {
+            bail_on!(PROS_ERR, pros_sys::optical_enable_gesture(self.port));
+        }
+        self.gesture_detection_enabled = true;
+        Ok(())
+    }
+
+    /// Disables gesture detection.
+    pub fn disable_gesture_detection(&mut self) -> Result<(), OpticalError> {
+        unsafe {
+            bail_on!(PROS_ERR, pros_sys::optical_disable_gesture(self.port));
+        }
+        self.gesture_detection_enabled = false;
+        Ok(())
+    }
+
+    /// Returns whether gesture detection is enabled.
+    pub fn gesture_detection_enabled(&self) -> bool {
+        self.gesture_detection_enabled
+    }
+
+    /// Returns the direction of the last gesture, or `None` if no gesture has been detected.
+    ///
+    /// Gestures are cleared 500 milliseconds after they are detected.
+    pub fn last_gesture_direction(&self) -> Result<Option<GestureDirection>, OpticalError> {
+        if !self.gesture_detection_enabled {
+            return Err(OpticalError::GestureDetectionDisabled);
+        }
+
+        let direction = unsafe { bail_on!(PROS_ERR, pros_sys::optical_get_gesture(self.port)) };
+        Ok(match direction {
+            pros_sys::OPTICAL_UP => Some(GestureDirection::Up),
+            pros_sys::OPTICAL_DOWN => Some(GestureDirection::Down),
+            pros_sys::OPTICAL_LEFT => Some(GestureDirection::Left),
+            pros_sys::OPTICAL_RIGHT => Some(GestureDirection::Right),
+            _ => None,
+        })
+    }
+
+    /// Returns the raw data of the last gesture.
+    pub fn last_gesture_raw(&self) -> Result<GestureRaw, OpticalError> {
+        if !self.gesture_detection_enabled {
+            return Err(OpticalError::GestureDetectionDisabled);
+        }
+
+        let raw = unsafe { pros_sys::optical_get_gesture_raw(self.port) };
+        bail_on!(PROS_ERR as u32, raw.time);
+
+        Ok(GestureRaw {
+            up: raw.udata,
+            down: raw.ddata,
+            left: raw.ldata,
+            right: raw.rdata,
+            gesture_type: raw.r#type,
+            count: raw.count,
+            time: raw.time,
+        })
+    }
+}
+
+impl SmartDevice for OpticalSensor {
+    fn port_index(&self) -> u8 {
+        self.port
+    }
+
+    fn device_type(&self) -> DeviceType {
+        DeviceType::Optical
+    }
+}
+
+/// Processed color readings from an optical sensor.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct OpticalRgb {
+    pub red: f64,
+    pub green: f64,
+    pub blue: f64,
+    pub brightness: f64,
+}
+
+/// Raw readings from each channel of an optical sensor.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct OpticalRaw {
+    pub red: u32,
+    pub green: u32,
+    pub blue: u32,
+    pub clear: u32,
+}
+
+/// The direction of a gesture detected by an optical sensor.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GestureDirection {
+    Up,
+    Down,
+    Left,
+    Right,
+}
+
+/// Raw gesture data from an optical sensor.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct GestureRaw {
+    pub up: u8,
+    pub down: u8,
+    pub left: u8,
+    pub right: u8,
+    pub gesture_type: u8,
+    /// Number of gestures detected.
+    pub count: u16,
+    /// Time since the gesture was detected in milliseconds.
+    pub time: u32,
+}
+
+#[derive(Debug, Snafu)]
+pub enum OpticalError {
+    #[snafu(display("LED PWM value must be between 0 and 100."))]
+    InvalidLedPwm,
+    #[snafu(display("Integration time must be between 3 and 712 milliseconds."))]
+    InvalidIntegrationTime,
+    #[snafu(display("Gesture detection is not enabled."))]
+    GestureDetectionDisabled,
+    #[snafu(display("{source}"), context(false))]
+    Port { source: PortError },
+}
+
+map_errno! {
+    OpticalError {}
+    inherit PortError;
+}
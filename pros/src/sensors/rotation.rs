@@ -2,13 +2,18 @@
 //!
 //! Rotation sensors operate on the same [`Position`] type as motors to measure rotation.
 
+use core::time::Duration;
+
 use pros_sys::PROS_ERR;
 
 use crate::{
+    devices::{validate_port, DeviceType, SmartDevice, SmartPort},
     error::{bail_on, PortError},
     position::Position,
 };
 
+use super::Encoder;
+
 /// A physical rotation sensor plugged into a port.
 pub struct RotationSensor {
     port: u8,
@@ -18,7 +23,9 @@ pub struct RotationSensor {
 impl RotationSensor {
     /// Creates a new rotation sensor on the given port.
     /// Whether or not the sensor should be reversed on creation can be specified.
-    pub fn new(port: u8, reversed: bool) -> Result<Self, PortError> {
+    pub fn new(port: SmartPort, reversed: bool) -> Result<Self, PortError> {
+        let port = port.index();
+        validate_port(port, DeviceType::Rotation)?;
         unsafe {
             bail_on!(PROS_ERR, pros_sys::rotation_reset_position(port));
             if reversed {
@@ -66,13 +73,97 @@ impl RotationSensor {
         self.set_reversed(!self.reversed)
     }
 
-    //TODO: See if this is accurate enough or consider switching to get_position function.
     /// Gets the current position of the sensor.
+    ///
+    /// Unlike [`angle`](Self::angle), the position keeps counting past a full rotation.
     pub fn position(&self) -> Result<Position, PortError> {
+        Ok(unsafe {
+            Position::from_degrees(
+                bail_on!(PROS_ERR, pros_sys::rotation_get_position(self.port)) as f64 / 100.0,
+            )
+        })
+    }
+
+    /// Gets the absolute angle of the sensor, from 0 to 360 degrees.
+    ///
+    /// The angle is kept even when the sensor loses power, so it can be used to find the absolute
+    /// position of a mechanism that rotates less than a full turn.
+    pub fn angle(&self) -> Result<Position, PortError> {
         Ok(unsafe {
             Position::from_degrees(
                 bail_on!(PROS_ERR, pros_sys::rotation_get_angle(self.port)) as f64 / 100.0,
             )
         })
     }
+
+    /// Gets the velocity of the sensor in RPM.
+    pub fn velocity(&self) -> Result<f64, PortError> {
+        // Centidegrees per second to rotations per minute.
+        Ok(
+            unsafe { bail_on!(PROS_ERR, pros_sys::rotation_get_velocity(self.port)) } as f64
+                / 600.0,
+        )
+    }
+
+    /// Reads the position, angle and velocity of the sensor together.
+    pub fn sample(&self) -> Result<RotationSample, PortError> {
+        Ok(RotationSample {
+            position: self.position()?,
+            angle: self.angle()?,
+            velocity: self.velocity()?,
+        })
+    }
+
+    /// Sets the position to the current absolute [`angle`](Self::angle) of the sensor.
+    pub fn reset(&mut self) -> Result<(), PortError> {
+        unsafe {
+            bail_on!(PROS_ERR, pros_sys::rotation_reset(self.port));
+        }
+        Ok(())
+    }
+
+    /// Sets how often the sensor sends new data.
+    ///
+    /// The interval is rounded down to a multiple of 5 milliseconds, with a minimum of 5 milliseconds.
+    /// The default is 10 milliseconds.
+    pub fn set_data_rate(&self, interval: Duration) -> Result<(), PortError> {
+        let rate = (interval.as_millis() as u32).max(pros_sys::ROTATION_MINIMUM_DATA_RATE);
+        unsafe {
+            bail_on!(PROS_ERR, pros_sys::rotation_set_data_rate(self.port, rate));
+        }
+        Ok(())
+    }
+}
+
+impl SmartDevice for RotationSensor {
+    fn port_index(&self) -> u8 {
+        self.port
+    }
+
+    fn device_type(&self) -> DeviceType {
+        DeviceType::Rotation
+    }
+}
+
+impl Encoder for RotationSensor {
+    type Error = PortError;
+
+    fn position(&self) -> Result<Position, PortError> {
+        RotationSensor::position(self)
+    }
+
+    fn zero(&mut self) -> Result<(), PortError> {
+        RotationSensor::zero(self)
+    }
+}
+
+/// Readings from a rotation sensor taken together.
+#[derive(Debug, Clone, Copy)]
+pub struct RotationSample {
+    /// The position of the sensor, which counts past a full rotation.
+    pub position: Position,
+    /// The absolute angle of the sensor, from 0 to 360 degrees.
+    pub angle: Position,
+    /// The velocity of the sensor in RPM.
+    pub velocity: f64,
 }
@@ -0,0 +1,100 @@
+//! Sampling sensors at a fixed rate.
+//!
+//! A [`SampleStream`] calls a sensor read at a fixed interval on the async runtime and yields each reading
+//! as a [`Sample`] along with the time it was taken. Errors from the read are yielded as items instead of
+//! ending the stream, so a sensor that is briefly unplugged doesn't stop the stream.
+//!
+//! Any read can be sampled with [`sample_stream`], and the [`Encoder`](super::Encoder), [`Gyro`](super::Gyro)
+//! and [`RangeFinder`](super::RangeFinder) traits provide streams of their main reading.
+//!
+//! Example of waiting for an object to come close to a distance sensor:
+//! ```rust
+//! use futures::StreamExt;
+//!
+//! let mut distances = distance_sensor.distance_stream(Duration::from_millis(10));
+//! while let Some(sample) = distances.next().await {
+//!     if sample?.value < 100.0 {
+//!         break;
+//!     }
+//! }
+//! ```
+
+use core::{
+    future::Future,
+    pin::Pin,
+    task::{Context, Poll},
+    time::Duration,
+};
+
+use futures::Stream;
+
+use crate::task::{sleep_until, SleepFuture};
+
+/// A sensor reading and the time it was taken.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Sample<T> {
+    pub value: T,
+    /// The time the reading was taken, measured from when the program started.
+    pub timestamp: Duration,
+}
+
+/// A stream of sensor readings taken at a fixed rate.
+///
+/// The stream never ends, so it should be stopped by dropping it.
+pub struct SampleStream<F> {
+    read: F,
+    interval: u32,
+    next_deadline: u32,
+    sleep: Option<SleepFuture>,
+}
+
+// The read function is never pinned.
+impl<F> Unpin for SampleStream<F> {}
+
+/// Returns a stream that calls `read` every `interval`, starting immediately.
+///
+/// If the stream is not polled often enough to keep up, readings are skipped rather than taken in a burst.
+pub fn sample_stream<T, E, F>(interval: Duration, read: F) -> SampleStream<F>
+where
+    F: FnMut() -> Result<T, E>,
+{
+    SampleStream {
+        read,
+        interval: interval.as_millis() as u32,
+        next_deadline: unsafe { pros_sys::millis() },
+        sleep: None,
+    }
+}
+
+impl<T, E, F> Stream for SampleStream<F>
+where
+    F: FnMut() -> Result<T, E>,
+{
+    type Item = Result<Sample<T>, E>;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let this = &mut *self;
+        if let Some(sleep) = &mut this.sleep {
+            if Pin::new(sleep).poll(cx).is_pending() {
+                return Poll::Pending;
+            }
+        }
+
+        let now = unsafe { pros_sys::millis() };
+        let value = (this.read)();
+
+        // Deadlines are absolute so that the time spent reading doesn't delay later samples.
+        // If the stream fell behind, the missed samples are skipped.
+        this.next_deadline += this.interval;
+        if this.next_deadline <= now {
+            let missed = (now - this.next_deadline) / this.interval.max(1) + 1;
+            this.next_deadline += missed * this.interval;
+        }
+        this.sleep = Some(sleep_until(this.next_deadline));
+
+        Poll::Ready(Some(value.map(|value| Sample {
+            value,
+            timestamp: Duration::from_millis(now as u64),
+        })))
+    }
+}
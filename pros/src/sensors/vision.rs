@@ -1,14 +1,30 @@
 //! Vision sensor device.
 //!
 //! Vision sensors take in a zero point at creation.
+//!
+//! Objects are detected using [`VisionSignature`]s, which can be tuned in the Vision Utility and loaded with
+//! [`VisionSignature::parse_utility`]. Several signatures can be combined into a [`VisionCode`].
+//!
+//! Example of tracking red game objects:
+//! ```rust
+//! let mut vision = VisionSensor::new(peripherals.port_1, VisionZeroPoint::Center)?;
+//! let red = VisionSignature::parse_utility(
+//!     "vision::signature RED (1, 8099, 8893, 8496, -1505, -949, -1227, 3.000, 0);",
+//! )?;
+//! vision.set_signature(1, &red)?;
+//! let objects = vision.objects_by_signature(1)?;
+//! ```
 
 extern crate alloc;
 use alloc::vec::Vec;
 use pros_sys::{PROS_ERR, VISION_OBJECT_ERR_SIG};
 use snafu::Snafu;
 
+pub mod tracker;
+
 use crate::{
-    error::{bail_errno, bail_on, map_errno, PortError},
+    devices::{validate_port, DeviceType, SmartDevice, SmartPort},
+    error::{bail_errno, bail_on, map_errno, take_errno, FromErrno, PortError},
     lvgl::colors::LcdColor,
 };
 
@@ -19,7 +35,9 @@ pub struct VisionSensor {
 
 impl VisionSensor {
     /// Creates a new vision sensor.
-    pub fn new(port: u8, zero: VisionZeroPoint) -> Result<Self, crate::error::PortError> {
+    pub fn new(port: SmartPort, zero: VisionZeroPoint) -> Result<Self, PortError> {
+        let port = port.index();
+        validate_port(port, DeviceType::Vision)?;
         unsafe {
             bail_on!(PROS_ERR, pros_sys::vision_set_zero_point(port, zero as _));
         }
@@ -32,16 +50,72 @@ impl VisionSensor {
         unsafe { pros_sys::vision_get_by_size(self.port, n).try_into() }
     }
 
+    /// Returns the nth largest object matching the given signature ID (from 1 to 7).
+    pub fn nth_largest_object_by_signature(
+        &self,
+        n: u32,
+        signature_id: u8,
+    ) -> Result<VisionObject, VisionError> {
+        unsafe { pros_sys::vision_get_by_sig(self.port, n, signature_id as _).try_into() }
+    }
+
+    /// Returns the nth largest object matching the given color code.
+    pub fn nth_largest_object_by_code(
+        &self,
+        n: u32,
+        code: VisionCode,
+    ) -> Result<VisionObject, VisionError> {
+        unsafe { pros_sys::vision_get_by_code(self.port, n, code.0).try_into() }
+    }
+
     /// Returns a list of all objects in order of size (largest to smallest).
     pub fn objects(&self) -> Result<Vec<VisionObject>, VisionError> {
-        let obj_count = self.num_objects()?;
-        let mut objects_buf = Vec::with_capacity(obj_count);
+        self.read_objects(|count, objects| unsafe {
+            pros_sys::vision_read_by_size(self.port, 0, count, objects)
+        })
+    }
 
-        unsafe {
-            pros_sys::vision_read_by_size(self.port, 0, obj_count as _, objects_buf.as_mut_ptr());
+    /// Returns a list of all objects matching the given signature ID (from 1 to 7),
+    /// in order of size (largest to smallest).
+    pub fn objects_by_signature(&self, signature_id: u8) -> Result<Vec<VisionObject>, VisionError> {
+        self.read_objects(|count, objects| unsafe {
+            pros_sys::vision_read_by_sig(self.port, 0, signature_id as _, count, objects)
+        })
+    }
+
+    /// Returns a list of all objects matching the given color code, in order of size (largest to smallest).
+    pub fn objects_by_code(&self, code: VisionCode) -> Result<Vec<VisionObject>, VisionError> {
+        self.read_objects(|count, objects| unsafe {
+            pros_sys::vision_read_by_code(self.port, 0, code.0, count, objects)
+        })
+    }
+
+    /// Reads up to [`num_objects`](Self::num_objects) objects with the given read function.
+    fn read_objects(
+        &self,
+        read: impl FnOnce(u32, *mut pros_sys::vision_object_s_t) -> i32,
+    ) -> Result<Vec<VisionObject>, VisionError> {
+        let object_count = self.num_objects()?;
+        if object_count == 0 {
+            return Ok(Vec::new());
         }
 
-        bail_errno!();
+        let mut objects_buf = Vec::with_capacity(object_count);
+        let read_count = read(object_count as _, objects_buf.as_mut_ptr());
+        if read_count == PROS_ERR {
+            return match take_errno() {
+                // No objects matched the filter.
+                pros_sys::error::EDOM => Ok(Vec::new()),
+                errno => Err(VisionError::from_errno(errno)
+                    .unwrap_or_else(|| panic!("Unknown errno code {errno}"))),
+            };
+        }
+        // ENXIO is set when fewer objects than requested match, which isn't an error here.
+        take_errno();
+
+        unsafe {
+            objects_buf.set_len((read_count.max(0) as usize).min(object_count));
+        }
 
         Ok(objects_buf
             .into_iter()
@@ -49,6 +123,55 @@ impl VisionSensor {
             .collect())
     }
 
+    /// Stores a signature on the sensor under the given ID (from 1 to 7).
+    ///
+    /// Signatures are stored in volatile memory, so they are lost when the sensor loses power.
+    pub fn set_signature(
+        &mut self,
+        id: u8,
+        signature: &VisionSignature,
+    ) -> Result<(), VisionError> {
+        let signature = pros_sys::vision_signature_s_t::from(*signature);
+        unsafe {
+            bail_on!(
+                PROS_ERR,
+                pros_sys::vision_set_signature(self.port, id, &signature)
+            );
+        }
+        Ok(())
+    }
+
+    /// Reads the signature stored on the sensor under the given ID (from 1 to 7).
+    pub fn signature(&self, id: u8) -> Result<VisionSignature, VisionError> {
+        let signature = unsafe { pros_sys::vision_get_signature(self.port, id) };
+        if signature.id == VISION_OBJECT_ERR_SIG as u8 {
+            bail_errno!();
+            return Err(VisionError::ReadingFailed);
+        }
+        Ok(signature.into())
+    }
+
+    /// Creates a color code from two to five signature IDs.
+    ///
+    /// Color codes detect objects where the signatures are next to each other in the given order.
+    pub fn create_color_code(&self, signature_ids: &[u8]) -> Result<VisionCode, VisionError> {
+        if !(2..=5).contains(&signature_ids.len())
+            || signature_ids.iter().any(|id| !(1..=7).contains(id))
+        {
+            return Err(VisionError::InvalidIdentifier);
+        }
+
+        let id = |index: usize| signature_ids.get(index).copied().unwrap_or(0) as u32;
+        // The color code has no error value, so errors can only be detected through errno,
+        // which has to be cleared first so that an error left over from an earlier call isn't reported.
+        take_errno();
+        let code = unsafe {
+            pros_sys::vision_create_color_code(self.port, id(0), id(1), id(2), id(3), id(4))
+        };
+        bail_errno!();
+        Ok(VisionCode(code))
+    }
+
     /// Returns the number of objects seen by the camera.
     pub fn num_objects(&self) -> Result<usize, PortError> {
         unsafe {
@@ -61,62 +184,123 @@ impl VisionSensor {
     }
 
     /// Get the current exposure percentage of the vision sensor. The returned result should be within 0.0 to 1.5.
-    pub fn exposure(&self) -> f32 {
-        unsafe { (pros_sys::vision_get_exposure(self.port) as f32) * 1.5 / 150.0 }
+    pub fn exposure(&self) -> Result<f32, VisionError> {
+        let exposure = unsafe { bail_on!(PROS_ERR, pros_sys::vision_get_exposure(self.port)) };
+        Ok(exposure as f32 * 1.5 / 150.0)
     }
 
     /// Get the current white balance of the vision sensor.
-    pub fn current_white_balance(&self) -> Rgb {
-        unsafe { (pros_sys::vision_get_white_balance(self.port) as u32).into() }
+    pub fn current_white_balance(&self) -> Result<Rgb, VisionError> {
+        let rgb = unsafe { bail_on!(PROS_ERR, pros_sys::vision_get_white_balance(self.port)) };
+        Ok((rgb as u32).into())
     }
 
     /// Sets the exposure percentage of the vision sensor. Should be between 0.0 and 1.5.
-    pub fn set_exposure(&mut self, exposure: f32) {
+    pub fn set_exposure(&mut self, exposure: f32) -> Result<(), VisionError> {
+        let exposure = (exposure.clamp(0.0, 1.5) * 150.0 / 1.5) as u8;
         unsafe {
-            pros_sys::vision_set_exposure(self.port, (exposure * 150.0 / 1.5) as u8);
+            bail_on!(PROS_ERR, pros_sys::vision_set_exposure(self.port, exposure));
         }
+        Ok(())
     }
 
-    /// Sets the white balance of the vision sensor.
-    pub fn set_white_balance(&mut self, white_balance: WhiteBalance) {
+    /// Enables or disables automatic white balance.
+    pub fn set_auto_white_balance(&mut self, enabled: bool) -> Result<(), VisionError> {
         unsafe {
-            match white_balance {
-                WhiteBalance::Auto => pros_sys::vision_set_auto_white_balance(self.port, 1),
-                WhiteBalance::Rgb(rgb) => {
-                    // Turn off automatic white balance
-                    pros_sys::vision_set_auto_white_balance(self.port, 0);
-                    pros_sys::vision_set_white_balance(
-                        self.port,
-                        <Rgb as Into<u32>>::into(rgb) as i32,
-                    )
+            bail_on!(
+                PROS_ERR,
+                pros_sys::vision_set_auto_white_balance(self.port, enabled as u8)
+            );
+        }
+        Ok(())
+    }
+
+    /// Sets the white balance of the vision sensor.
+    pub fn set_white_balance(&mut self, white_balance: WhiteBalance) -> Result<(), VisionError> {
+        match white_balance {
+            WhiteBalance::Auto => self.set_auto_white_balance(true)?,
+            WhiteBalance::Rgb(rgb) => {
+                // Turn off automatic white balance
+                self.set_auto_white_balance(false)?;
+                unsafe {
+                    bail_on!(
+                        PROS_ERR,
+                        pros_sys::vision_set_white_balance(
+                            self.port,
+                            <Rgb as Into<u32>>::into(rgb) as i32,
+                        )
+                    );
                 }
-            };
+            }
         }
+        Ok(())
     }
 
     /// Sets the point that object positions are relative to, in other words where (0, 0) is or the zero point.
-    pub fn set_zero_point(&mut self, zero: VisionZeroPoint) {
+    pub fn set_zero_point(&mut self, zero: VisionZeroPoint) -> Result<(), VisionError> {
         unsafe {
-            pros_sys::vision_set_zero_point(self.port, zero as _);
+            bail_on!(
+                PROS_ERR,
+                pros_sys::vision_set_zero_point(self.port, zero as _)
+            );
         }
+        Ok(())
     }
 
     /// Sets the color of the led.
-    pub fn set_led(&mut self, mode: LedMode) {
-        unsafe {
-            match mode {
-                LedMode::Off => pros_sys::vision_clear_led(self.port),
-                LedMode::On(rgb) => {
+    pub fn set_led(&mut self, mode: LedMode) -> Result<(), VisionError> {
+        match mode {
+            LedMode::Off => self.clear_led()?,
+            LedMode::On(rgb) => unsafe {
+                bail_on!(
+                    PROS_ERR,
                     pros_sys::vision_set_led(self.port, <Rgb as Into<u32>>::into(rgb) as i32)
-                }
-            };
+                );
+            },
         }
+        Ok(())
+    }
+
+    /// Gives control of the led back to the sensor, which uses it to show the color of the largest object.
+    pub fn clear_led(&mut self) -> Result<(), VisionError> {
+        unsafe {
+            bail_on!(PROS_ERR, pros_sys::vision_clear_led(self.port));
+        }
+        Ok(())
+    }
+
+    /// Enables or disables the sensor's Wi-Fi.
+    ///
+    /// While Wi-Fi is enabled, the sensor can be connected to the Vision Utility wirelessly.
+    pub fn set_wifi_mode(&mut self, enabled: bool) -> Result<(), VisionError> {
+        unsafe {
+            bail_on!(
+                PROS_ERR,
+                pros_sys::vision_set_wifi_mode(self.port, enabled as u8)
+            );
+        }
+        Ok(())
+    }
+}
+
+impl SmartDevice for VisionSensor {
+    fn port_index(&self) -> u8 {
+        self.port
+    }
+
+    fn device_type(&self) -> DeviceType {
+        DeviceType::Vision
     }
 }
 
 //TODO: figure out how coordinates are done.
-#[derive(Debug)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub struct VisionObject {
+    /// The ID of the signature (or the color code) that matched this object.
+    pub signature: u16,
+    /// The angle of a color code object in degrees.
+    pub angle: i16,
+
     pub top: i16,
     pub left: i16,
     pub middle_x: i16,
@@ -135,6 +319,8 @@ impl TryFrom<pros_sys::vision_object_s_t> for VisionObject {
         }
 
         Ok(Self {
+            signature: value.signature,
+            angle: value.angle,
             top: value.top_coord,
             left: value.left_coord,
             middle_x: value.x_middle_coord,
@@ -145,14 +331,162 @@ impl TryFrom<pros_sys::vision_object_s_t> for VisionObject {
     }
 }
 
+/// Parameters the vision sensor uses to detect objects of one color.
+///
+/// Signatures are usually tuned in the Vision Utility, which generates code containing their values.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct VisionSignature {
+    pub id: u8,
+    pub u_min: i32,
+    pub u_max: i32,
+    pub u_mean: i32,
+    pub v_min: i32,
+    pub v_max: i32,
+    pub v_mean: i32,
+    /// How far the color can stray from the signature and still be detected.
+    pub range: f32,
+    pub rgb: u32,
+    pub signature_type: u32,
+}
+
+impl VisionSignature {
+    /// Creates a signature from the values generated by the Vision Utility.
+    #[allow(clippy::too_many_arguments)]
+    pub fn from_utility(
+        id: u8,
+        u_min: i32,
+        u_max: i32,
+        u_mean: i32,
+        v_min: i32,
+        v_max: i32,
+        v_mean: i32,
+        range: f32,
+        signature_type: u32,
+    ) -> Self {
+        unsafe {
+            pros_sys::vision_signature_from_utility(
+                id as _,
+                u_min,
+                u_max,
+                u_mean,
+                v_min,
+                v_max,
+                v_mean,
+                range,
+                signature_type as _,
+            )
+        }
+        .into()
+    }
+
+    /// Parses a signature from a line of code copied out of the Vision Utility.
+    ///
+    /// Both the C++ (`vision::signature SIG_1 (1, 8099, 8893, 8496, -1505, -949, -1227, 3.000, 0);`)
+    /// and C (`vision_signature_from_utility(1, 8099, ...)`) forms are accepted.
+    pub fn parse_utility(text: &str) -> Result<Self, VisionError> {
+        let arguments = text
+            .split_once('(')
+            .and_then(|(_, rest)| rest.split_once(')'))
+            .map(|(arguments, _)| arguments)
+            .ok_or(VisionError::InvalidSignature)?;
+
+        let mut values = arguments.split(',').map(str::trim);
+        let mut next = || values.next().ok_or(VisionError::InvalidSignature);
+        let integer = |value: &str| {
+            value
+                .parse::<i32>()
+                .map_err(|_| VisionError::InvalidSignature)
+        };
+
+        let id = next()?
+            .parse::<u8>()
+            .map_err(|_| VisionError::InvalidSignature)?;
+        let u_min = integer(next()?)?;
+        let u_max = integer(next()?)?;
+        let u_mean = integer(next()?)?;
+        let v_min = integer(next()?)?;
+        let v_max = integer(next()?)?;
+        let v_mean = integer(next()?)?;
+        let range = next()?
+            .trim_end_matches('f')
+            .parse::<f32>()
+            .map_err(|_| VisionError::InvalidSignature)?;
+        let signature_type = integer(next()?)? as u32;
+        if values.next().is_some() {
+            return Err(VisionError::InvalidSignature);
+        }
+
+        Ok(Self::from_utility(
+            id,
+            u_min,
+            u_max,
+            u_mean,
+            v_min,
+            v_max,
+            v_mean,
+            range,
+            signature_type,
+        ))
+    }
+}
+
+impl From<pros_sys::vision_signature_s_t> for VisionSignature {
+    fn from(value: pros_sys::vision_signature_s_t) -> Self {
+        Self {
+            id: value.id,
+            u_min: value.u_min,
+            u_max: value.u_max,
+            u_mean: value.u_mean,
+            v_min: value.v_min,
+            v_max: value.v_max,
+            v_mean: value.v_mean,
+            range: value.range,
+            rgb: value.rgb,
+            signature_type: value.r#type,
+        }
+    }
+}
+
+impl From<VisionSignature> for pros_sys::vision_signature_s_t {
+    fn from(value: VisionSignature) -> Self {
+        Self {
+            id: value.id,
+            _pad: [0; 3],
+            range: value.range,
+            u_min: value.u_min,
+            u_max: value.u_max,
+            u_mean: value.u_mean,
+            v_min: value.v_min,
+            v_max: value.v_max,
+            v_mean: value.v_mean,
+            rgb: value.rgb,
+            r#type: value.signature_type,
+        }
+    }
+}
+
+/// A color code, which detects objects made of several signatures next to each other.
+///
+/// Color codes are created with [`VisionSensor::create_color_code`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct VisionCode(pub u16);
+
+/// A 24-bit color.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
 pub struct Rgb {
-    r: u8,
-    g: u8,
-    b: u8,
+    pub r: u8,
+    pub g: u8,
+    pub b: u8,
 }
 
 impl Rgb {
-    pub fn new(r: u8, g: u8, b: u8) -> Self {
+    pub const BLACK: Self = Self::new(0, 0, 0);
+    pub const WHITE: Self = Self::new(255, 255, 255);
+    pub const RED: Self = Self::new(255, 0, 0);
+    pub const GREEN: Self = Self::new(0, 255, 0);
+    pub const BLUE: Self = Self::new(0, 0, 255);
+
+    pub const fn new(r: u8, g: u8, b: u8) -> Self {
         Self { r, g, b }
     }
 }
@@ -196,6 +530,7 @@ impl From<LcdColor> for Rgb {
     }
 }
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 #[repr(u32)]
 pub enum VisionZeroPoint {
     TopLeft,
@@ -214,14 +549,20 @@ pub enum LedMode {
 
 #[derive(Debug, Snafu)]
 pub enum VisionError {
+    #[snafu(display("The camera could not be read."))]
+    ReadingFailed,
     #[snafu(display(
         "The index specified was higher than the total number of objects seen by the camera."
     ))]
-    ReadingFailed,
-    #[snafu(display("The camera could not be read."))]
     IndexTooHigh,
     #[snafu(display("Port already taken."))]
     PortTaken,
+    #[snafu(display(
+        "Signature IDs must be between 1 and 7, and color codes need 2 to 5 of them."
+    ))]
+    InvalidIdentifier,
+    #[snafu(display("The signature could not be parsed from the Vision Utility output."))]
+    InvalidSignature,
     #[snafu(display("{source}"), context(false))]
     Port { source: PortError },
 }
@@ -231,6 +572,8 @@ map_errno! {
         EHOSTDOWN => Self::ReadingFailed,
         EDOM => Self::IndexTooHigh,
         EACCES => Self::PortTaken,
+        EINVAL => Self::InvalidIdentifier,
+        EAGAIN => Self::ReadingFailed,
     }
     inherit PortError;
 }
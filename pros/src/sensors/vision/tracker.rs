@@ -0,0 +1,229 @@
+//! Tracking vision objects across frames.
+//!
+//! The vision sensor reports a fresh list of objects every frame with no way of telling which object is which.
+//! A [`VisionTracker`] matches each new object to the closest object from the last frame
+//! (as long as it is within a gating distance), so every object keeps a stable ID while it stays in view.
+//! Tracked objects also have an estimated velocity in pixels per second, and their position can be
+//! converted into a bearing angle for aiming at them.
+//!
+//! Example of turning towards the same game object every frame:
+//! ```rust
+//! let mut tracker = VisionTracker::new(VisionZeroPoint::Center, 40.0);
+//! loop {
+//!     let tracks = tracker.update_from_sensor(&vision)?;
+//!     if let Some(target) = tracks.iter().max_by_key(|track| track.age) {
+//!         println!("Object {} is {} degrees to the right", target.id, target.bearing().0);
+//!     }
+//!     sleep(Duration::from_millis(50)).await;
+//! }
+//! ```
+
+use alloc::vec::Vec;
+
+use super::{VisionError, VisionObject, VisionSensor, VisionZeroPoint};
+
+/// Horizontal field of view of the vision sensor in degrees.
+pub const VISION_HORIZONTAL_FOV: f64 = 61.0;
+/// Vertical field of view of the vision sensor in degrees.
+pub const VISION_VERTICAL_FOV: f64 = 41.0;
+
+/// An object that has been followed across frames.
+#[derive(Debug, Clone, Copy)]
+pub struct TrackedObject {
+    /// An ID that stays the same while the object is being tracked.
+    pub id: u32,
+    /// The latest detection of the object.
+    pub object: VisionObject,
+    /// Estimated velocity of the object's center in pixels per second, as `(x, y)`.
+    pub velocity: (f64, f64),
+    /// How many frames the object has been seen in.
+    pub age: u32,
+    /// How many frames in a row the object has not been seen in.
+    pub missed_frames: u32,
+    /// The time the object was last seen, in milliseconds.
+    last_seen: u32,
+    zero_point: VisionZeroPoint,
+}
+
+impl TrackedObject {
+    /// Returns the offset of the object's center from the center of the image in pixels, as `(x, y)`.
+    pub fn center_offset(&self) -> (f64, f64) {
+        let (x, y) = (self.object.middle_x as f64, self.object.middle_y as f64);
+        match self.zero_point {
+            VisionZeroPoint::Center => (x, y),
+            VisionZeroPoint::TopLeft => (
+                x - pros_sys::VISION_FOV_WIDTH as f64 / 2.0,
+                y - pros_sys::VISION_FOV_HEIGHT as f64 / 2.0,
+            ),
+        }
+    }
+
+    /// Returns the angle from the sensor to the object's center in degrees, as `(horizontal, vertical)`.
+    ///
+    /// Objects to the right of or below the center of the image have positive angles.
+    pub fn bearing(&self) -> (f64, f64) {
+        let (x, y) = self.center_offset();
+        (
+            pixel_to_angle(x, pros_sys::VISION_FOV_WIDTH as f64, VISION_HORIZONTAL_FOV),
+            pixel_to_angle(y, pros_sys::VISION_FOV_HEIGHT as f64, VISION_VERTICAL_FOV),
+        )
+    }
+
+    /// Returns the time in seconds between when the object was last seen and `timestamp` (in milliseconds).
+    fn time_since_seen(&self, timestamp: u32) -> f64 {
+        timestamp.wrapping_sub(self.last_seen) as f64 / 1000.0
+    }
+
+    /// Returns where the object's center is expected to be `dt` seconds after it was last seen.
+    fn predicted_center(&self, dt: f64) -> (f64, f64) {
+        (
+            self.object.middle_x as f64 + self.velocity.0 * dt,
+            self.object.middle_y as f64 + self.velocity.1 * dt,
+        )
+    }
+}
+
+/// Converts a pixel offset from the center of the image into an angle using a pinhole camera model.
+fn pixel_to_angle(offset: f64, resolution: f64, fov: f64) -> f64 {
+    let focal_length = (resolution / 2.0) / libm::tan(fov.to_radians() / 2.0);
+    libm::atan(offset / focal_length).to_degrees()
+}
+
+/// Follows vision objects across frames with nearest-neighbor matching.
+#[derive(Debug, Clone)]
+pub struct VisionTracker {
+    zero_point: VisionZeroPoint,
+    /// Objects further than this many pixels from where a tracked object was expected to be are never matched to it.
+    pub gate: f64,
+    /// How many frames an object can go unseen before it stops being tracked.
+    pub max_missed_frames: u32,
+    /// Whether objects must have the same signature to be matched.
+    pub match_signatures: bool,
+    /// How much each new measurement affects the velocity estimate, from 0 to 1.
+    pub velocity_smoothing: f64,
+    tracks: Vec<TrackedObject>,
+    next_id: u32,
+}
+
+impl VisionTracker {
+    /// Creates a new tracker.
+    ///
+    /// `zero_point` must match the zero point of the sensor being tracked,
+    /// and `gate` is the furthest (in pixels) an object can move between frames and still be matched.
+    pub fn new(zero_point: VisionZeroPoint, gate: f64) -> Self {
+        Self {
+            zero_point,
+            gate,
+            max_missed_frames: 5,
+            match_signatures: true,
+            velocity_smoothing: 0.5,
+            tracks: Vec::new(),
+            next_id: 0,
+        }
+    }
+
+    /// Returns every object currently being tracked, including ones that were missed in recent frames.
+    pub fn tracks(&self) -> &[TrackedObject] {
+        &self.tracks
+    }
+
+    /// Returns the tracked object with the given ID, if it is still being tracked.
+    pub fn track(&self, id: u32) -> Option<&TrackedObject> {
+        self.tracks.iter().find(|track| track.id == id)
+    }
+
+    /// Forgets every tracked object.
+    pub fn clear(&mut self) {
+        self.tracks.clear();
+    }
+
+    /// Reads the objects seen by a sensor and updates the tracker with them.
+    pub fn update_from_sensor(
+        &mut self,
+        sensor: &VisionSensor,
+    ) -> Result<&[TrackedObject], VisionError> {
+        let objects = sensor.objects()?;
+        Ok(self.update(&objects, unsafe { pros_sys::millis() }))
+    }
+
+    /// Updates the tracker with the objects detected in a new frame captured at `timestamp` (in milliseconds).
+    pub fn update(&mut self, objects: &[VisionObject], timestamp: u32) -> &[TrackedObject] {
+        // Every pairing of a track and a detection that is within the gate, closest first.
+        let mut candidates = Vec::new();
+        for (track_index, track) in self.tracks.iter().enumerate() {
+            // Tracks that were missed in recent frames are predicted from when they were last seen.
+            let (predicted_x, predicted_y) =
+                track.predicted_center(track.time_since_seen(timestamp));
+            for (object_index, object) in objects.iter().enumerate() {
+                if self.match_signatures && object.signature != track.object.signature {
+                    continue;
+                }
+                let distance = libm::hypot(
+                    object.middle_x as f64 - predicted_x,
+                    object.middle_y as f64 - predicted_y,
+                );
+                if distance <= self.gate {
+                    candidates.push((distance, track_index, object_index));
+                }
+            }
+        }
+        candidates.sort_by(|a, b| a.0.total_cmp(&b.0));
+
+        let mut track_matched = alloc::vec![false; self.tracks.len()];
+        let mut object_matched = alloc::vec![false; objects.len()];
+        for (_, track_index, object_index) in candidates {
+            if track_matched[track_index] || object_matched[object_index] {
+                continue;
+            }
+            track_matched[track_index] = true;
+            object_matched[object_index] = true;
+
+            let track = &mut self.tracks[track_index];
+            let object = objects[object_index];
+            let dt = track.time_since_seen(timestamp);
+            if dt > 0.0 {
+                let measured = (
+                    (object.middle_x - track.object.middle_x) as f64 / dt,
+                    (object.middle_y - track.object.middle_y) as f64 / dt,
+                );
+                let alpha = self.velocity_smoothing;
+                track.velocity = (
+                    alpha * measured.0 + (1.0 - alpha) * track.velocity.0,
+                    alpha * measured.1 + (1.0 - alpha) * track.velocity.1,
+                );
+            }
+            track.object = object;
+            track.age += 1;
+            track.missed_frames = 0;
+            track.last_seen = timestamp;
+        }
+
+        for (track, matched) in self.tracks.iter_mut().zip(&track_matched) {
+            if !matched {
+                track.missed_frames += 1;
+            }
+        }
+        let max_missed_frames = self.max_missed_frames;
+        self.tracks
+            .retain(|track| track.missed_frames <= max_missed_frames);
+
+        for (object, _) in objects
+            .iter()
+            .zip(object_matched)
+            .filter(|(_, matched)| !matched)
+        {
+            self.tracks.push(TrackedObject {
+                id: self.next_id,
+                object: *object,
+                velocity: (0.0, 0.0),
+                age: 1,
+                missed_frames: 0,
+                last_seen: timestamp,
+                zero_point: self.zero_point,
+            });
+            self.next_id = self.next_id.wrapping_add(1);
+        }
+
+        &self.tracks
+    }
+}
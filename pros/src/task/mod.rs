@@ -350,6 +350,11 @@ pub fn sleep(duration: core::time::Duration) -> SleepFuture {
     }
 }
 
+/// Returns a future that will complete once [`pros_sys::millis`] has passed `target_millis`.
+pub(crate) fn sleep_until(target_millis: u32) -> SleepFuture {
+    SleepFuture { target_millis }
+}
+
 /// Returns the task the function was called from.
 pub fn current() -> TaskHandle {
     unsafe {
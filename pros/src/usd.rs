@@ -1,4 +1,84 @@
+//! MicroSD card access.
+//!
+//! Files are read from the root of the SD card, so `"paths/skills.csv"` refers to `/usd/paths/skills.csv`.
+//! Reading a whole file is usually enough for robot code, such as loading paths
+//! with [`load_path`](crate::motion::import::load_path).
+
+use alloc::{ffi::CString, format, string::String, vec::Vec};
+
+use snafu::Snafu;
+
 /// Checks if an SD card is installed.
 pub fn usd_installed() -> bool {
     unsafe { pros_sys::misc::usd_is_installed() == 1 }
 }
+
+/// Reads the entire contents of a file on the SD card.
+pub fn read(path: &str) -> Result<Vec<u8>, UsdError> {
+    if !usd_installed() {
+        return Err(UsdError::NotInstalled);
+    }
+
+    let full_path = if path.starts_with("/usd/") {
+        String::from(path)
+    } else {
+        format!("/usd/{}", path.trim_start_matches('/'))
+    };
+    let c_path = CString::new(full_path).map_err(|_| UsdError::InvalidPath {
+        path: String::from(path),
+    })?;
+
+    let file = unsafe { pros_sys::fopen(c_path.as_ptr(), c"rb".as_ptr()) };
+    if file.is_null() {
+        return Err(match crate::error::take_errno() {
+            pros_sys::error::ENOENT => UsdError::NotFound {
+                path: String::from(path),
+            },
+            _ => UsdError::ReadFailed {
+                path: String::from(path),
+            },
+        });
+    }
+
+    let mut contents = Vec::new();
+    let mut buffer = [0u8; 512];
+    let failed = loop {
+        let read = unsafe { pros_sys::fread(buffer.as_mut_ptr().cast(), 1, buffer.len(), file) };
+        contents.extend_from_slice(&buffer[..read]);
+        if read < buffer.len() {
+            break unsafe { pros_sys::ferror(file) } != 0;
+        }
+    };
+    unsafe {
+        pros_sys::fclose(file);
+    }
+
+    if failed {
+        return Err(UsdError::ReadFailed {
+            path: String::from(path),
+        });
+    }
+
+    Ok(contents)
+}
+
+/// Reads the entire contents of a UTF-8 text file on the SD card.
+pub fn read_to_string(path: &str) -> Result<String, UsdError> {
+    String::from_utf8(read(path)?).map_err(|_| UsdError::InvalidUtf8 {
+        path: String::from(path),
+    })
+}
+
+#[derive(Debug, Snafu)]
+pub enum UsdError {
+    #[snafu(display("No SD card is installed."))]
+    NotInstalled,
+    #[snafu(display("`{path}` is not a valid file path."))]
+    InvalidPath { path: String },
+    #[snafu(display("`{path}` does not exist on the SD card."))]
+    NotFound { path: String },
+    #[snafu(display("Failed to read `{path}` from the SD card."))]
+    ReadFailed { path: String },
+    #[snafu(display("`{path}` is not valid UTF-8 text."))]
+    InvalidUtf8 { path: String },
+}